@@ -0,0 +1,70 @@
+// A set of dense indices below a fixed bound packed into machine words
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(super) struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    const WORD_BITS: usize = u64::BITS as usize;
+
+    pub(super) fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(Self::WORD_BITS)],
+        }
+    }
+
+    // Returns whether the index hasn't been in the set before
+    pub(super) fn insert(&mut self, idx: usize) -> bool {
+        let word = &mut self.words[idx / Self::WORD_BITS];
+        let mask = 1 << (idx % Self::WORD_BITS);
+        let inserted = *word & mask == 0;
+        *word |= mask;
+        inserted
+    }
+
+    pub(super) fn union_with(&mut self, other: &Self) {
+        self.words
+            .iter_mut()
+            .zip(other.words.iter())
+            .for_each(|(word, other_word)| *word |= other_word);
+    }
+
+    pub(super) fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(word_idx, word)| {
+            let mut rest = *word;
+
+            std::iter::from_fn(move || {
+                if rest == 0 {
+                    return None;
+                }
+
+                let bit = rest.trailing_zeros() as usize;
+                rest &= rest - 1;
+                Some(word_idx * Self::WORD_BITS + bit)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bitset_unit_1() {
+        let mut set = BitSet::new(130);
+        assert_eq!(set.iter().count(), 0);
+
+        assert!(set.insert(0));
+        assert!(set.insert(64));
+        assert!(set.insert(129));
+        assert!(!set.insert(64));
+
+        let mut other = BitSet::new(130);
+        other.insert(1);
+        other.insert(64);
+        set.union_with(&other);
+
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0, 1, 64, 129]);
+    }
+}
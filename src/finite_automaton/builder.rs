@@ -0,0 +1,180 @@
+use std::fmt;
+
+use crate::{
+    AutomatonAlphabet, AutomatonBuildError, AutomatonState, AutomatonTransition, FiniteAutomaton,
+    FiniteAutomatonBuilder,
+};
+
+impl FiniteAutomatonBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_state(&mut self) -> AutomatonState {
+        self.automaton.add_state()
+    }
+
+    pub fn mark_start(&mut self, state: AutomatonState) -> &mut Self {
+        self.automaton.start_states.insert(state);
+        self
+    }
+
+    pub fn mark_accept(&mut self, state: AutomatonState) -> &mut Self {
+        self.automaton.accept_states.insert(state);
+        self
+    }
+
+    pub fn add_transition(
+        &mut self,
+        from: AutomatonState,
+        symbol: char,
+        to: AutomatonState,
+    ) -> &mut Self {
+        self.add(from, AutomatonTransition::Symbol(symbol), to)
+    }
+
+    pub fn add_epsilon(&mut self, from: AutomatonState, to: AutomatonState) -> &mut Self {
+        self.add(from, AutomatonTransition::Epsilon, to)
+    }
+
+    // Symbols which don't have to appear on any transition, see FiniteAutomaton::set_alphabet()
+    pub fn alphabet(&mut self, alphabet: &AutomatonAlphabet) -> &mut Self {
+        self.automaton.set_alphabet(alphabet);
+        self
+    }
+
+    // Fails if some state has been used without being added first
+    pub fn build(&self) -> Result<FiniteAutomaton, AutomatonBuildError> {
+        if let Some(state) = self.unknown_source {
+            return Err(AutomatonBuildError::UnknownState(state));
+        }
+
+        let automaton = &self.automaton;
+        let is_known = |state: &AutomatonState| automaton.transitions.contains_key(state);
+
+        let unknown_state = automaton
+            .start_states
+            .iter()
+            .chain(automaton.accept_states.iter())
+            .chain(
+                automaton
+                    .transitions
+                    .values()
+                    .flat_map(|state_transitions| state_transitions.values().flatten()),
+            )
+            .find(|state| !is_known(state));
+
+        match unknown_state {
+            Some(state) => Err(AutomatonBuildError::UnknownState(*state)),
+            None => Ok(automaton.clone()),
+        }
+    }
+
+    // A transition from an unknown state would make it known, so it's only remembered
+    fn add(
+        &mut self,
+        from: AutomatonState,
+        symbol: AutomatonTransition,
+        to: AutomatonState,
+    ) -> &mut Self {
+        match self.automaton.transitions.contains_key(&from) {
+            true => self.automaton.add_transition(from, symbol, to),
+            false => {
+                self.unknown_source.get_or_insert(from);
+            }
+        }
+
+        self
+    }
+}
+
+impl fmt::Display for AutomatonBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownState(state) => write!(f, "Unknown state {}", state),
+        }
+    }
+}
+
+impl std::error::Error for AutomatonBuildError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Dfa;
+
+    #[test]
+    fn build_unit_1() {
+        // Words over {a, b} with an even number of a's
+        let mut builder = FiniteAutomatonBuilder::new();
+        let even = builder.add_state();
+        let odd = builder.add_state();
+
+        builder
+            .mark_start(even)
+            .mark_accept(even)
+            .add_transition(even, 'a', odd)
+            .add_transition(odd, 'a', even)
+            .add_transition(even, 'b', even)
+            .add_transition(odd, 'b', odd);
+
+        let automaton = builder.build().unwrap();
+
+        assert!(automaton.accepts_word("abba"));
+        assert!(!automaton.accepts_word("ab"));
+        assert!(automaton.states().eq([even, odd]));
+        assert!(automaton.is_start(even));
+        assert!(!automaton.is_accept(odd));
+        assert_eq!(automaton.transitions().count(), 4);
+        assert!(automaton.transitions_from(odd).eq([
+            (AutomatonTransition::Symbol('a'), even),
+            (AutomatonTransition::Symbol('b'), odd)
+        ]));
+
+        assert!(Dfa::try_from(automaton).is_ok());
+    }
+
+    #[test]
+    fn build_unit_2() {
+        let mut builder = FiniteAutomatonBuilder::new();
+        let start = builder.add_state();
+        builder.mark_start(start).add_epsilon(start, 7);
+
+        assert_eq!(
+            builder.build().unwrap_err(),
+            AutomatonBuildError::UnknownState(7)
+        );
+
+        let mut builder = FiniteAutomatonBuilder::new();
+        let state = builder.add_state();
+        builder.add_transition(3, 'a', state);
+        builder.add_state();
+        builder.add_state();
+        builder.add_state();
+
+        assert_eq!(
+            builder.build().unwrap_err(),
+            AutomatonBuildError::UnknownState(3)
+        );
+
+        let mut builder = FiniteAutomatonBuilder::new();
+        builder.mark_accept(0);
+
+        assert_eq!(
+            builder.build().unwrap_err(),
+            AutomatonBuildError::UnknownState(0)
+        );
+
+        let mut builder = FiniteAutomaton::builder();
+        let state = builder.add_state();
+        let automaton = builder
+            .mark_start(state)
+            .alphabet(&AutomatonAlphabet::from([AutomatonTransition::Symbol('z')]))
+            .build()
+            .unwrap();
+        assert!(automaton
+            .get_alphabet()
+            .contains(&AutomatonTransition::Symbol('z')));
+        assert!(automaton.is_empty());
+    }
+}
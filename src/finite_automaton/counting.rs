@@ -0,0 +1,349 @@
+use std::collections::BTreeMap;
+
+use num_bigint::BigUint;
+
+use super::graph::strongly_connected_components;
+use crate::{AutomatonState, AutomatonTransition, FiniteAutomaton, WordGrowth};
+
+// The useful part of the determinized automaton with densely renumbered states
+#[derive(Debug, Clone)]
+pub(super) struct DenseDfa {
+    pub(super) start: Option<usize>,
+    pub(super) accept: Vec<bool>,
+    // Transitions of every state sorted by symbol
+    pub(super) transitions: Vec<Vec<(char, usize)>>,
+}
+
+impl DenseDfa {
+    pub(super) fn new(automaton: &FiniteAutomaton) -> Self {
+        let dfa = automaton.determinized();
+        let useful_states = dfa.useful_states();
+
+        let mapping: BTreeMap<AutomatonState, usize> = useful_states
+            .iter()
+            .enumerate()
+            .map(|(idx, state)| (*state, idx))
+            .collect();
+
+        let transitions = useful_states
+            .iter()
+            .map(|state| {
+                // SAFETY: every state must have been created via
+                // new_state() and thus is present in transitions map
+                dfa.transitions[state]
+                    .iter()
+                    .filter_map(|(symbol, dest_states)| match symbol {
+                        AutomatonTransition::Symbol(sym) => dest_states
+                            .iter()
+                            .find_map(|dest_state| mapping.get(dest_state))
+                            .map(|dest_idx| (*sym, *dest_idx)),
+                        AutomatonTransition::Epsilon => None,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            start: dfa
+                .start_states
+                .iter()
+                .find_map(|state| mapping.get(state).copied()),
+            accept: useful_states
+                .iter()
+                .map(|state| dfa.accept_states.contains(state))
+                .collect(),
+            transitions,
+        }
+    }
+
+    // table[len][state] is the number of words of length len leading from state to acceptance
+    pub(super) fn count_table(&self, max_len: usize) -> Vec<Vec<BigUint>> {
+        let mut table = vec![self.accept_counts()];
+
+        for len in 1..=max_len {
+            let curr = self.count_step(&table[len - 1]);
+            table.push(curr);
+        }
+
+        table
+    }
+
+    // The number of empty words leading from every state to acceptance
+    fn accept_counts(&self) -> Vec<BigUint> {
+        self.accept
+            .iter()
+            .map(|accept| BigUint::from(*accept as u8))
+            .collect()
+    }
+
+    // Extends the counts for words of some length to the words one symbol longer
+    fn count_step(&self, prev: &[BigUint]) -> Vec<BigUint> {
+        self.transitions
+            .iter()
+            .map(|state_transitions| {
+                state_transitions
+                    .iter()
+                    .map(|(_, dest_idx)| &prev[*dest_idx])
+                    .sum()
+            })
+            .collect()
+    }
+
+    fn adjacency(&self) -> Vec<Vec<usize>> {
+        self.transitions
+            .iter()
+            .map(|state_transitions| {
+                state_transitions
+                    .iter()
+                    .map(|(_, dest_idx)| *dest_idx)
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl FiniteAutomaton {
+    pub fn count_words(&self, len: usize) -> BigUint {
+        let dfa = DenseDfa::new(self);
+
+        let Some(start) = dfa.start else {
+            return BigUint::default();
+        };
+
+        let mut curr = dfa.accept_counts();
+
+        for _ in 0..len {
+            curr = dfa.count_step(&curr);
+        }
+
+        curr.swap_remove(start)
+    }
+
+    pub fn count_words_up_to(&self, max_len: usize) -> BigUint {
+        let dfa = DenseDfa::new(self);
+
+        let Some(start) = dfa.start else {
+            return BigUint::default();
+        };
+
+        let mut curr = dfa.accept_counts();
+        let mut total = curr[start].clone();
+
+        for _ in 0..max_len {
+            curr = dfa.count_step(&curr);
+            total += &curr[start];
+        }
+
+        total
+    }
+
+    pub fn word_growth(&self) -> WordGrowth {
+        let dfa = DenseDfa::new(self);
+        let adjacency = dfa.adjacency();
+        let component = strongly_connected_components(&adjacency);
+        let components_num = component.iter().max().map_or(0, |max| max + 1);
+
+        // Number of states and inner edges of every component
+        let mut states_num = vec![0_usize; components_num];
+        let mut edges_num = vec![0_usize; components_num];
+
+        adjacency
+            .iter()
+            .enumerate()
+            .for_each(|(state, dest_states)| {
+                states_num[component[state]] += 1;
+
+                dest_states
+                    .iter()
+                    .filter(|dest_state| component[**dest_state] == component[state])
+                    .for_each(|_| edges_num[component[state]] += 1);
+            });
+
+        // A component with more inner edges than states contains
+        // two different cycles, which makes the growth exponential
+        let rate = (0..components_num)
+            .filter(|comp| edges_num[*comp] > states_num[*comp])
+            .map(|comp| Self::spectral_radius(&adjacency, &component, comp))
+            .reduce(f64::max);
+
+        if let Some(rate) = rate {
+            return WordGrowth::Exponential(rate);
+        }
+
+        // Otherwise every component is either a single cycle or no cycle at all, and the
+        // degree is defined by the maximum number of cycles passed on a single path.
+        // Components are numbered so that successors go first
+        let mut cycles_on_path = vec![0_usize; components_num];
+        let mut comp_states = vec![Vec::<usize>::new(); components_num];
+
+        component
+            .iter()
+            .enumerate()
+            .for_each(|(state, comp)| comp_states[*comp].push(state));
+
+        for comp in 0..components_num {
+            let successors_max = comp_states[comp]
+                .iter()
+                .flat_map(|state| adjacency[*state].iter())
+                .filter(|dest_state| component[**dest_state] != comp)
+                .map(|dest_state| cycles_on_path[component[*dest_state]])
+                .max()
+                .unwrap_or_default();
+
+            cycles_on_path[comp] = successors_max + (edges_num[comp] > 0) as usize;
+        }
+
+        match dfa.start.map(|start| cycles_on_path[component[start]]) {
+            None | Some(0) => WordGrowth::Finite(Self::count_finite(&dfa, &adjacency)),
+            Some(cycles) => WordGrowth::Polynomial(cycles - 1),
+        }
+    }
+
+    fn count_finite(dfa: &DenseDfa, adjacency: &[Vec<usize>]) -> BigUint {
+        let Some(start) = dfa.start else {
+            return BigUint::default();
+        };
+
+        // Every state is a component of its own in an acyclic graph,
+        // so their numbers give the order in which successors go first
+        let component = strongly_connected_components(adjacency);
+        let mut order: Vec<usize> = (0..adjacency.len()).collect();
+        order.sort_by_key(|state| component[*state]);
+
+        let mut words_num = vec![BigUint::default(); adjacency.len()];
+
+        for state in order {
+            let from_successors: BigUint = adjacency[state]
+                .iter()
+                .map(|dest_state| &words_num[*dest_state])
+                .sum();
+
+            words_num[state] = from_successors + BigUint::from(dfa.accept[state] as u8);
+        }
+
+        words_num.swap_remove(start)
+    }
+
+    // The largest eigenvalue of the component's adjacency matrix. Power iteration is
+    // run on (A + I) instead of A, which has the same eigenvectors but is aperiodic
+    fn spectral_radius(adjacency: &[Vec<usize>], component: &[usize], comp: usize) -> f64 {
+        const ITERATIONS_MAX: usize = 10_000;
+        const PRECISION: f64 = 1e-12;
+
+        let states: Vec<usize> = (0..adjacency.len())
+            .filter(|state| component[*state] == comp)
+            .collect();
+
+        let mut vector = vec![1_f64; adjacency.len()];
+        let mut radius = 0_f64;
+
+        for _ in 0..ITERATIONS_MAX {
+            let mut next = vec![0_f64; adjacency.len()];
+
+            states.iter().for_each(|state| {
+                next[*state] = vector[*state]
+                    + adjacency[*state]
+                        .iter()
+                        .filter(|dest_state| component[**dest_state] == comp)
+                        .map(|dest_state| vector[*dest_state])
+                        .sum::<f64>();
+            });
+
+            let norm = states
+                .iter()
+                .map(|state| next[*state])
+                .fold(0_f64, f64::max);
+            states.iter().for_each(|state| next[*state] /= norm);
+
+            let converged = (norm - 1_f64 - radius).abs() < PRECISION;
+            radius = norm - 1_f64;
+            vector = next;
+
+            if converged {
+                break;
+            }
+        }
+
+        radius
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finite_automaton::tests::from_string;
+
+    #[test]
+    fn count_words_unit_1() {
+        let automaton = from_string("(a|b)*abb");
+
+        assert_eq!(automaton.count_words(0), BigUint::from(0_u8));
+        assert_eq!(automaton.count_words(3), BigUint::from(1_u8));
+        assert_eq!(automaton.count_words(5), BigUint::from(4_u8));
+        assert_eq!(automaton.count_words_up_to(5), BigUint::from(7_u8));
+
+        // Ambiguous expression, every word is still counted once
+        let automaton = from_string("(a|aa)*");
+        assert_eq!(automaton.count_words(10), BigUint::from(1_u8));
+        assert_eq!(automaton.count_words_up_to(10), BigUint::from(11_u8));
+    }
+
+    #[test]
+    fn count_words_unit_2() {
+        // 2^100 doesn't fit into any primitive integer type
+        let automaton = from_string("[ab]*");
+        assert_eq!(automaton.count_words(100), BigUint::from(2_u8).pow(100));
+
+        assert_eq!(
+            FiniteAutomaton::default().count_words_up_to(3),
+            BigUint::from(0_u8)
+        );
+
+        // No length is too large to ask for
+        assert_eq!(
+            FiniteAutomaton::default().count_words(usize::MAX),
+            BigUint::from(0_u8)
+        );
+        assert_eq!(
+            FiniteAutomaton::default().count_words_up_to(usize::MAX),
+            BigUint::from(0_u8)
+        );
+    }
+
+    #[test]
+    fn word_growth_unit_1() {
+        assert_eq!(
+            from_string("(a|b){3}|c").word_growth(),
+            WordGrowth::Finite(BigUint::from(9_u8))
+        );
+        assert_eq!(
+            FiniteAutomaton::default().word_growth(),
+            WordGrowth::Finite(BigUint::from(0_u8))
+        );
+        assert_eq!(from_string("a*").word_growth(), WordGrowth::Polynomial(0));
+        assert_eq!(
+            from_string("a*b*c*").word_growth(),
+            WordGrowth::Polynomial(2)
+        );
+        assert_eq!(
+            from_string("a*|b*").word_growth(),
+            WordGrowth::Polynomial(0)
+        );
+    }
+
+    #[test]
+    fn word_growth_unit_2() {
+        let WordGrowth::Exponential(rate) = from_string("[0-9]*").word_growth() else {
+            panic!("exponential growth expected");
+        };
+
+        assert!((rate - 10_f64).abs() < 1e-6);
+
+        // Fibonacci numbers grow as the golden ratio
+        let WordGrowth::Exponential(rate) = from_string("(a|ba)*").word_growth() else {
+            panic!("exponential growth expected");
+        };
+
+        assert!((rate - (1_f64 + 5_f64.sqrt()) / 2_f64).abs() < 1e-6);
+    }
+}
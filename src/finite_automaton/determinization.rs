@@ -0,0 +1,290 @@
+use std::{
+    collections::{hash_map::RandomState, BTreeMap, HashMap},
+    fmt,
+    hash::BuildHasher,
+    mem,
+};
+
+use super::bitset::BitSet;
+use crate::{
+    AutomatonState, AutomatonTransition, DeterminizeBudget, DeterminizeError, DeterminizeStats,
+    FiniteAutomaton,
+};
+
+// Every subset of nfa states is stored once and mapped to its dfa state
+#[derive(Debug, Clone)]
+struct SubsetInterner {
+    // Subsets in order of their dfa states
+    subsets: Vec<BitSet>,
+    // Dfa states by the hashes of their subsets, which keeps the subsets out of the keys
+    interned: HashMap<u64, Vec<AutomatonState>>,
+    hasher: RandomState,
+    subset_size: usize,
+    budget: DeterminizeBudget,
+    stats: DeterminizeStats,
+}
+
+impl DeterminizeBudget {
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    pub fn max_states(mut self, max_states: usize) -> Self {
+        self.max_states = Some(max_states);
+        self
+    }
+
+    pub fn max_memory(mut self, max_memory: usize) -> Self {
+        self.max_memory = Some(max_memory);
+        self
+    }
+
+    fn is_exceeded(&self, stats: &DeterminizeStats) -> bool {
+        self.max_states.is_some_and(|max| stats.dfa_states > max)
+            || self.max_memory.is_some_and(|max| stats.memory > max)
+    }
+}
+
+impl DeterminizeStats {
+    pub fn dfa_states(&self) -> usize {
+        self.dfa_states
+    }
+
+    // The number of dfa states whose transitions have been built
+    pub fn processed_states(&self) -> usize {
+        self.processed_states
+    }
+
+    pub fn memory(&self) -> usize {
+        self.memory
+    }
+}
+
+impl DeterminizeError {
+    pub fn stats(&self) -> DeterminizeStats {
+        match self {
+            Self::Blowup(stats) => *stats,
+        }
+    }
+}
+
+impl fmt::Display for DeterminizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Blowup(stats) => write!(
+                f,
+                "Determinization blowup ({} dfa states, {} processed, {} bytes of subsets)",
+                stats.dfa_states, stats.processed_states, stats.memory
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DeterminizeError {}
+
+impl SubsetInterner {
+    fn intern(
+        &mut self,
+        dfa: &mut FiniteAutomaton,
+        subset: BitSet,
+    ) -> Result<AutomatonState, DeterminizeError> {
+        let hash = self.hasher.hash_one(&subset);
+        let same_hash = self.interned.entry(hash).or_default();
+
+        if let Some(dfa_state) = same_hash
+            .iter()
+            .find(|dfa_state| self.subsets[**dfa_state] == subset)
+        {
+            return Ok(*dfa_state);
+        }
+
+        self.stats.dfa_states += 1;
+        self.stats.memory += self.subset_size;
+
+        if self.budget.is_exceeded(&self.stats) {
+            return Err(DeterminizeError::Blowup(self.stats));
+        }
+
+        let dfa_state = dfa.add_state();
+        same_hash.push(dfa_state);
+        self.subsets.push(subset);
+        Ok(dfa_state)
+    }
+}
+
+impl FiniteAutomaton {
+    // Epsilon transitions are followed on the fly, so they don't have to be eliminated first
+    pub fn to_dfa(nfa: &FiniteAutomaton) -> Self {
+        // SAFETY: an unlimited budget can't be exceeded
+        Self::try_to_dfa(nfa, DeterminizeBudget::unlimited()).unwrap()
+    }
+
+    // Subsets of nfa states are kept as bitsets and interned, so that every one of them is
+    // stored once. DFA states are numbered in breadth-first order following sorted symbols
+    pub fn try_to_dfa(
+        nfa: &FiniteAutomaton,
+        budget: DeterminizeBudget,
+    ) -> Result<Self, DeterminizeError> {
+        let mut dfa = Self {
+            alphabet: nfa.alphabet.clone(),
+            auto_trim: nfa.auto_trim,
+            track_origins: nfa.track_origins,
+            ..Self::default()
+        };
+
+        let states: Vec<AutomatonState> = nfa.transitions.keys().copied().collect();
+        let (component, closures) = nfa.epsilon_closures(&states);
+        let closure = |idx: usize| &closures[component[idx]];
+
+        let symbols: Vec<AutomatonTransition> = nfa.get_alphabet().into_iter().collect();
+        let symbol_idx: BTreeMap<AutomatonTransition, usize> = symbols
+            .iter()
+            .enumerate()
+            .map(|(idx, symbol)| (*symbol, idx))
+            .collect();
+
+        let state_idx: BTreeMap<AutomatonState, usize> = states
+            .iter()
+            .enumerate()
+            .map(|(idx, state)| (*state, idx))
+            .collect();
+
+        // Symbol transitions of every nfa state as (symbol, destination) pairs
+        let transitions: Vec<Vec<(usize, usize)>> = states
+            .iter()
+            .map(|state| {
+                nfa.transitions[state]
+                    .iter()
+                    .filter(|(symbol, _)| **symbol != AutomatonTransition::Epsilon)
+                    .flat_map(|(symbol, dest_states)| {
+                        dest_states
+                            .iter()
+                            .map(|dest_state| (symbol_idx[symbol], state_idx[dest_state]))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let is_accept: Vec<bool> = states
+            .iter()
+            .map(|state| nfa.accept_states.contains(state))
+            .collect();
+
+        let mut interner = SubsetInterner {
+            subsets: Vec::new(),
+            interned: HashMap::new(),
+            hasher: RandomState::new(),
+            subset_size: mem::size_of::<BitSet>()
+                + states.len().div_ceil(u64::BITS as usize) * mem::size_of::<u64>(),
+            budget,
+            stats: DeterminizeStats {
+                dfa_states: 0,
+                processed_states: 0,
+                memory: 0,
+            },
+        };
+
+        let mut start_subset = BitSet::new(states.len());
+        nfa.start_states
+            .iter()
+            .for_each(|state| start_subset.union_with(closure(state_idx[state])));
+
+        let start_state = interner.intern(&mut dfa, start_subset)?;
+        dfa.start_states.insert(start_state);
+
+        while let Some(subset) = interner.subsets.get(interner.stats.processed_states) {
+            let curr_state = interner.stats.processed_states;
+            let mut next_subsets = vec![None::<BitSet>; symbols.len()];
+
+            subset.iter().for_each(|nfa_idx| {
+                if is_accept[nfa_idx] {
+                    dfa.accept_states.insert(curr_state);
+                }
+
+                transitions[nfa_idx].iter().for_each(|(symbol, dest_idx)| {
+                    next_subsets[*symbol]
+                        .get_or_insert_with(|| BitSet::new(states.len()))
+                        .union_with(closure(*dest_idx));
+                });
+            });
+
+            for (symbol, next_subset) in next_subsets.into_iter().enumerate() {
+                let Some(next_subset) = next_subset else {
+                    continue;
+                };
+
+                let dfa_to = interner.intern(&mut dfa, next_subset)?;
+                dfa.add_transition(curr_state, symbols[symbol], dfa_to);
+            }
+
+            interner.stats.processed_states += 1;
+        }
+
+        if dfa.track_origins {
+            dfa.origins = interner
+                .subsets
+                .iter()
+                .enumerate()
+                .map(|(dfa_state, subset)| {
+                    (
+                        dfa_state,
+                        subset.iter().map(|nfa_idx| states[nfa_idx]).collect(),
+                    )
+                })
+                .collect();
+        }
+
+        if dfa.auto_trim {
+            dfa.trim();
+        }
+
+        Ok(dfa)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{finite_automaton::tests::from_string, LanguageEquivalence};
+
+    #[test]
+    fn try_to_dfa_unit_1() {
+        let nfa = from_string("(a|b)*a(a|b){20}");
+
+        let error =
+            FiniteAutomaton::try_to_dfa(&nfa, DeterminizeBudget::unlimited().max_states(1000))
+                .unwrap_err();
+
+        assert!(matches!(error, DeterminizeError::Blowup(_)));
+        assert_eq!(error.stats().dfa_states(), 1001);
+        assert!(error.stats().processed_states() < 1000);
+
+        let error =
+            FiniteAutomaton::try_to_dfa(&nfa, DeterminizeBudget::unlimited().max_memory(1 << 16))
+                .unwrap_err();
+
+        assert!(error.stats().memory() > 1 << 16);
+        assert!(error.to_string().starts_with("Determinization blowup"));
+    }
+
+    #[test]
+    fn try_to_dfa_unit_2() {
+        let nfa = from_string("(a|b)*a(a|b){3}");
+        let dfa = FiniteAutomaton::to_dfa(&nfa);
+        let states_num = dfa.transitions.len();
+        let budget = DeterminizeBudget::unlimited().max_states(states_num);
+
+        assert_eq!(
+            FiniteAutomaton::try_to_dfa(&nfa, budget)
+                .unwrap()
+                .transitions,
+            dfa.transitions
+        );
+        assert!(dfa.is_deterministic());
+        assert_eq!(
+            FiniteAutomaton::equivalent(&dfa, &nfa),
+            LanguageEquivalence::Equal
+        );
+        assert!(FiniteAutomaton::try_to_dfa(&nfa, budget.max_states(states_num - 1)).is_err());
+    }
+}
@@ -0,0 +1,94 @@
+// Tarjan's algorithm without recursion, so that long chains of states
+// don't overflow the stack. Returns the component of every node; components
+// are numbered in reverse topological order, i.e. successors go first
+pub(super) fn strongly_connected_components(adjacency: &[Vec<usize>]) -> Vec<usize> {
+    const UNVISITED: usize = usize::MAX;
+
+    let nodes_num = adjacency.len();
+    let mut index = vec![UNVISITED; nodes_num];
+    let mut lowlink = vec![0_usize; nodes_num];
+    let mut on_stack = vec![false; nodes_num];
+    let mut component = vec![UNVISITED; nodes_num];
+
+    let mut stack = Vec::<usize>::new();
+    let mut next_index = 0_usize;
+    let mut components_num = 0_usize;
+
+    for root in 0..nodes_num {
+        if index[root] != UNVISITED {
+            continue;
+        }
+
+        index[root] = next_index;
+        lowlink[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        let mut call_stack = vec![(root, 0_usize)];
+
+        while let Some((node, next_edge)) = call_stack.last_mut() {
+            let node = *node;
+
+            match adjacency[node].get(*next_edge).copied() {
+                Some(next) => {
+                    *next_edge += 1;
+
+                    if index[next] == UNVISITED {
+                        index[next] = next_index;
+                        lowlink[next] = next_index;
+                        next_index += 1;
+                        stack.push(next);
+                        on_stack[next] = true;
+                        call_stack.push((next, 0));
+                    } else if on_stack[next] {
+                        lowlink[node] = lowlink[node].min(index[next]);
+                    }
+                }
+                None => {
+                    call_stack.pop();
+
+                    if let Some((parent, _)) = call_stack.last() {
+                        lowlink[*parent] = lowlink[*parent].min(lowlink[node]);
+                    }
+
+                    if lowlink[node] == index[node] {
+                        // SAFETY: the node itself is still on the stack
+                        loop {
+                            let member = stack.pop().unwrap();
+                            on_stack[member] = false;
+                            component[member] = components_num;
+
+                            if member == node {
+                                break;
+                            }
+                        }
+
+                        components_num += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    component
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strongly_connected_components_unit_1() {
+        // 0 -> 1 <-> 2 -> 3, 3 -> 3
+        let component = strongly_connected_components(&[vec![1], vec![2], vec![1, 3], vec![3]]);
+
+        assert_eq!(component[1], component[2]);
+        assert_ne!(component[0], component[1]);
+        assert_ne!(component[2], component[3]);
+
+        // Successors go first
+        assert!(component[3] < component[1]);
+        assert!(component[1] < component[0]);
+    }
+}
@@ -0,0 +1,362 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{AutomatonState, AutomatonTransition, FiniteAutomaton};
+
+// Everything about a state that an isomorphism has to preserve
+type StateSignature = (bool, bool, Vec<(AutomatonTransition, usize)>, usize);
+
+impl FiniteAutomaton {
+    // Renumbers the states 0..n in breadth-first order from the start states following
+    // the transitions sorted by symbol, the unreachable states are numbered last
+    pub fn canonicalize(&mut self) {
+        let order = self.breadth_first_order();
+
+        let mapping: BTreeMap<AutomatonState, AutomatonState> = order
+            .iter()
+            .enumerate()
+            .map(|(idx, state)| (*state, idx))
+            .collect();
+
+        let mut canonical = Self {
+            alphabet: self.alphabet.clone(),
+            auto_trim: self.auto_trim,
+            track_origins: self.track_origins,
+            ..Self::default()
+        };
+
+        order.iter().for_each(|_| {
+            canonical.add_state();
+        });
+
+        canonical.start_states = self
+            .start_states
+            .iter()
+            .map(|state| mapping[state])
+            .collect();
+        canonical.accept_states = self
+            .accept_states
+            .iter()
+            .map(|state| mapping[state])
+            .collect();
+        canonical.origins = self
+            .origins
+            .iter()
+            .map(|(state, origin)| (mapping[state], origin.clone()))
+            .collect();
+
+        self.transitions
+            .iter()
+            .for_each(|(from, state_transitions)| {
+                state_transitions.iter().for_each(|(symbol, dest_states)| {
+                    dest_states.iter().for_each(|to| {
+                        canonical.add_transition(mapping[from], *symbol, mapping[to]);
+                    });
+                });
+            });
+
+        *self = canonical;
+    }
+
+    // Looks for a bijection between the states which preserves start and accept
+    // states along with every transition. Deterministic automatons leave no choice
+    // but the one forced by the transitions, nondeterministic ones may need backtracking
+    pub fn is_isomorphic(&self, other: &Self) -> bool {
+        if self.transitions.len() != other.transitions.len()
+            || self.start_states.len() != other.start_states.len()
+            || self.accept_states.len() != other.accept_states.len()
+        {
+            return false;
+        }
+
+        let self_signatures = self.signatures();
+        let other_signatures = other.signatures();
+
+        let order = self.breadth_first_order();
+        let reversed = self.reverse();
+
+        let mut mapping = BTreeMap::<AutomatonState, AutomatonState>::new();
+        let mut used = BTreeSet::<AutomatonState>::new();
+
+        // Candidates for a state, a predecessor that is already mapped
+        // narrows them down to the targets of its image
+        let candidates = |state: AutomatonState,
+                          mapping: &BTreeMap<AutomatonState, AutomatonState>|
+         -> Vec<AutomatonState> {
+            let mapped_predecessor = reversed.transitions[&state]
+                .iter()
+                .flat_map(|(symbol, sources)| sources.iter().map(move |source| (symbol, source)))
+                .find_map(|(symbol, source)| mapping.get(source).map(|image| (symbol, image)));
+
+            match mapped_predecessor {
+                Some((symbol, image)) => other.transitions[image]
+                    .get(symbol)
+                    .into_iter()
+                    .flatten()
+                    .copied()
+                    .collect(),
+                None => other.transitions.keys().copied().collect(),
+            }
+        };
+
+        let Some(first) = order.first() else {
+            return true;
+        };
+
+        let mut stack = vec![(candidates(*first, &mapping), 0_usize)];
+
+        while let Some(depth) = stack.len().checked_sub(1) {
+            let (state_candidates, next) = &mut stack[depth];
+            let state = order[depth];
+
+            // Undo the previous choice for this state before trying the next one
+            if let Some(previous) = mapping.remove(&state) {
+                used.remove(&previous);
+            }
+
+            let mut found = false;
+
+            while let Some(candidate) = state_candidates.get(*next).copied() {
+                *next += 1;
+
+                if used.contains(&candidate)
+                    || self_signatures[&state] != other_signatures[&candidate]
+                {
+                    continue;
+                }
+
+                mapping.insert(state, candidate);
+
+                if self.is_consistent(other, &reversed, &mapping, state) {
+                    used.insert(candidate);
+                    found = true;
+                    break;
+                }
+
+                mapping.remove(&state);
+            }
+
+            match found {
+                false => {
+                    stack.pop();
+                }
+                true if depth + 1 == order.len() => return true,
+                true => {
+                    let next_state = order[depth + 1];
+                    stack.push((candidates(next_state, &mapping), 0));
+                }
+            }
+        }
+
+        false
+    }
+
+    // Every state in order of breadth-first search from the start states
+    // followed by the unreachable ones
+    fn breadth_first_order(&self) -> Vec<AutomatonState> {
+        let mut order = Vec::<AutomatonState>::new();
+        let mut visited = BTreeSet::<AutomatonState>::new();
+
+        let roots = self
+            .start_states
+            .iter()
+            .chain(self.transitions.keys())
+            .copied()
+            .collect::<Vec<_>>();
+
+        for root in roots {
+            if !visited.insert(root) {
+                continue;
+            }
+
+            let mut curr = order.len();
+            order.push(root);
+
+            while curr < order.len() {
+                // SAFETY: every state must have been created via
+                // new_state() and thus is present in transitions map
+                self.transitions[&order[curr]]
+                    .values()
+                    .flatten()
+                    .for_each(|dest_state| {
+                        if visited.insert(*dest_state) {
+                            order.push(*dest_state);
+                        }
+                    });
+
+                curr += 1;
+            }
+        }
+
+        order
+    }
+
+    fn signatures(&self) -> BTreeMap<AutomatonState, StateSignature> {
+        let mut in_degree = BTreeMap::<AutomatonState, usize>::new();
+
+        self.transitions.values().for_each(|state_transitions| {
+            state_transitions.values().flatten().for_each(|dest_state| {
+                *in_degree.entry(*dest_state).or_default() += 1;
+            });
+        });
+
+        self.transitions
+            .iter()
+            .map(|(state, state_transitions)| {
+                let out_degree = state_transitions
+                    .iter()
+                    .filter(|(_, dest_states)| !dest_states.is_empty())
+                    .map(|(symbol, dest_states)| (*symbol, dest_states.len()))
+                    .collect();
+
+                let signature = (
+                    self.start_states.contains(state),
+                    self.accept_states.contains(state),
+                    out_degree,
+                    in_degree.get(state).copied().unwrap_or_default(),
+                );
+
+                (*state, signature)
+            })
+            .collect()
+    }
+
+    // Checks the transitions between the given state and the already mapped ones
+    fn is_consistent(
+        &self,
+        other: &Self,
+        reversed: &Self,
+        mapping: &BTreeMap<AutomatonState, AutomatonState>,
+        state: AutomatonState,
+    ) -> bool {
+        let has_image = |from: AutomatonState, symbol: &AutomatonTransition, to: AutomatonState| {
+            other.transitions[&mapping[&from]]
+                .get(symbol)
+                .is_some_and(|dest_states| dest_states.contains(&mapping[&to]))
+        };
+
+        let outgoing = self.transitions[&state]
+            .iter()
+            .all(|(symbol, dest_states)| {
+                dest_states
+                    .iter()
+                    .filter(|dest_state| mapping.contains_key(dest_state))
+                    .all(|dest_state| has_image(state, symbol, *dest_state))
+            });
+
+        let incoming = reversed.transitions[&state]
+            .iter()
+            .all(|(symbol, sources)| {
+                sources
+                    .iter()
+                    .filter(|source| mapping.contains_key(source))
+                    .all(|source| has_image(*source, symbol, state))
+            });
+
+        outgoing && incoming
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{finite_automaton::tests::from_string, MinimizationAlgorithm};
+
+    #[test]
+    fn canonicalize_unit_1() {
+        let mut nfa = from_string("a(b|c)*");
+        nfa.eliminate_epsilon();
+        nfa.canonicalize();
+
+        assert_eq!(nfa.last_state, nfa.transitions.len());
+        assert!(nfa.transitions.keys().copied().eq(0..nfa.last_state));
+        assert_eq!(nfa.start_states, BTreeSet::from([0]));
+
+        let mut dfa = FiniteAutomaton::to_dfa(&nfa);
+        dfa.make_full();
+        dfa.make_minimal();
+        dfa.canonicalize();
+
+        let a = AutomatonTransition::Symbol('a');
+        let b = AutomatonTransition::Symbol('b');
+        let c = AutomatonTransition::Symbol('c');
+
+        assert_eq!(dfa.start_states, BTreeSet::from([0]));
+        assert_eq!(dfa.accept_states, BTreeSet::from([1]));
+        assert_eq!(
+            dfa.transitions,
+            BTreeMap::from([
+                (
+                    0,
+                    BTreeMap::from([
+                        (a, BTreeSet::from([1])),
+                        (b, BTreeSet::from([2])),
+                        (c, BTreeSet::from([2])),
+                    ])
+                ),
+                (
+                    1,
+                    BTreeMap::from([
+                        (a, BTreeSet::from([2])),
+                        (b, BTreeSet::from([1])),
+                        (c, BTreeSet::from([1])),
+                    ])
+                ),
+                (
+                    2,
+                    BTreeMap::from([
+                        (a, BTreeSet::from([2])),
+                        (b, BTreeSet::from([2])),
+                        (c, BTreeSet::from([2])),
+                    ])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn canonicalize_unit_2() {
+        // Different constructions of the same language end up exactly the same
+        let mut left = from_string("(ab)*a");
+        left.make_minimal();
+        left.canonicalize();
+
+        let mut right = from_string("a(ba)*");
+        right.minimize_with(MinimizationAlgorithm::Brzozowski);
+        right.canonicalize();
+
+        assert_eq!(left.start_states, right.start_states);
+        assert_eq!(left.accept_states, right.accept_states);
+        assert_eq!(left.transitions, right.transitions);
+    }
+
+    #[test]
+    fn is_isomorphic_unit_1() {
+        let mut hopcroft = from_string("a((ba)*a(ab)* | a)*");
+        hopcroft.make_minimal();
+
+        let mut brzozowski = from_string("a((ba)*a(ab)* | a)*");
+        brzozowski.minimize_with(MinimizationAlgorithm::Brzozowski);
+
+        assert!(hopcroft.is_isomorphic(&brzozowski));
+        assert!(brzozowski.is_isomorphic(&hopcroft));
+
+        let mut other = from_string("a((ba)*a(ab)* | b)*");
+        other.make_minimal();
+
+        assert!(!hopcroft.is_isomorphic(&other));
+        assert!(FiniteAutomaton::default().is_isomorphic(&FiniteAutomaton::default()));
+    }
+
+    #[test]
+    fn is_isomorphic_unit_2() {
+        // Nondeterministic automatons with scrambled state numbers
+        let nfa = from_string("(a|ab)*(b|1)");
+
+        let mut canonical = nfa.clone();
+        canonical.canonicalize();
+
+        assert!(nfa.is_isomorphic(&canonical));
+        assert!(canonical.is_isomorphic(&nfa));
+        assert!(!nfa.is_isomorphic(&nfa.reverse()));
+    }
+}
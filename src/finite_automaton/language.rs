@@ -0,0 +1,391 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::{
+    AutomatonAlphabet, AutomatonState, AutomatonTransition, FiniteAutomaton, LanguageEquivalence,
+    LanguageInclusion,
+};
+
+type SubsetPair = (BTreeSet<AutomatonState>, BTreeSet<AutomatonState>);
+
+impl FiniteAutomaton {
+    pub fn equivalent(left: &Self, right: &Self) -> LanguageEquivalence {
+        match Self::find_distinguishing_word(left, right, |left, right| left != right) {
+            Some(word) => LanguageEquivalence::Counterexample(word),
+            None => LanguageEquivalence::Equal,
+        }
+    }
+
+    pub fn is_subset_of(&self, other: &Self) -> LanguageInclusion {
+        match Self::find_distinguishing_word(self, other, |left, right| left && !right) {
+            Some(word) => LanguageInclusion::Counterexample(word),
+            None => LanguageInclusion::Included,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reachable_states()
+            .iter()
+            .all(|state| !self.accept_states.contains(state))
+    }
+
+    pub fn is_universal(&self, alphabet: &AutomatonAlphabet) -> bool {
+        let mut universal = Self::default();
+        let state = universal.add_state();
+        universal.start_states.insert(state);
+        universal.accept_states.insert(state);
+
+        alphabet
+            .iter()
+            .filter(|symbol| **symbol != AutomatonTransition::Epsilon)
+            .for_each(|symbol| universal.add_transition(state, *symbol, state));
+
+        universal.is_subset_of(self) == LanguageInclusion::Included
+    }
+
+    pub fn is_finite(&self) -> bool {
+        let automaton = self.without_epsilon();
+        automaton.postorder(&automaton.useful_states()).is_some()
+    }
+
+    // Saturates at usize::MAX if there are too many words to count
+    pub fn len_if_finite(&self) -> Option<usize> {
+        let dfa = self.determinized();
+        let useful_states = dfa.useful_states();
+        let mut words_num = BTreeMap::<AutomatonState, usize>::new();
+
+        // Successors go first in postorder, so their counts are always ready
+        for state in dfa.postorder(&useful_states)? {
+            let mut state_words_num = dfa.accept_states.contains(&state) as usize;
+
+            // SAFETY: every state must have been created via
+            // new_state() and thus is present in transitions map
+            dfa.transitions[&state]
+                .values()
+                .flatten()
+                .for_each(|dest_state| {
+                    let dest_words_num = words_num.get(dest_state).copied().unwrap_or_default();
+                    state_words_num = state_words_num.saturating_add(dest_words_num);
+                });
+
+            words_num.insert(state, state_words_num);
+        }
+
+        Some(
+            dfa.start_states
+                .iter()
+                .map(|state| words_num.get(state).copied().unwrap_or_default())
+                .sum(),
+        )
+    }
+
+    // States that can be reached from some start state
+    pub(super) fn reachable_states(&self) -> BTreeSet<AutomatonState> {
+        let mut reachable = self.start_states.clone();
+        let mut stack: Vec<AutomatonState> = self.start_states.iter().copied().collect();
+
+        while let Some(state) = stack.pop() {
+            self.transitions
+                .get(&state)
+                .into_iter()
+                .flat_map(|state_transitions| state_transitions.values().flatten())
+                .for_each(|dest_state| {
+                    if reachable.insert(*dest_state) {
+                        stack.push(*dest_state);
+                    }
+                });
+        }
+
+        reachable
+    }
+
+    // States from which some accept state can be reached
+    pub(super) fn coreachable_states(&self) -> BTreeSet<AutomatonState> {
+        let mut reverse_transitions = BTreeMap::<AutomatonState, Vec<AutomatonState>>::new();
+
+        self.transitions
+            .iter()
+            .for_each(|(state, state_transitions)| {
+                state_transitions.values().flatten().for_each(|dest_state| {
+                    reverse_transitions
+                        .entry(*dest_state)
+                        .or_default()
+                        .push(*state);
+                });
+            });
+
+        let mut coreachable = self.accept_states.clone();
+        let mut stack: Vec<AutomatonState> = self.accept_states.iter().copied().collect();
+
+        while let Some(state) = stack.pop() {
+            reverse_transitions
+                .get(&state)
+                .into_iter()
+                .flatten()
+                .for_each(|source_state| {
+                    if coreachable.insert(*source_state) {
+                        stack.push(*source_state);
+                    }
+                });
+        }
+
+        coreachable
+    }
+
+    pub(super) fn useful_states(&self) -> BTreeSet<AutomatonState> {
+        self.reachable_states()
+            .intersection(&self.coreachable_states())
+            .copied()
+            .collect()
+    }
+
+    // Returns None if the subgraph induced by the given states has a cycle
+    pub(super) fn postorder(
+        &self,
+        states: &BTreeSet<AutomatonState>,
+    ) -> Option<Vec<AutomatonState>> {
+        let successors = |state: AutomatonState| -> Vec<AutomatonState> {
+            self.transitions
+                .get(&state)
+                .into_iter()
+                .flat_map(|state_transitions| state_transitions.values().flatten())
+                .filter(|dest_state| states.contains(dest_state))
+                .copied()
+                .collect()
+        };
+
+        let mut order = Vec::<AutomatonState>::new();
+        let mut visited = BTreeSet::<AutomatonState>::new();
+        let mut on_stack = BTreeSet::<AutomatonState>::new();
+
+        for root in states.iter() {
+            if !visited.insert(*root) {
+                continue;
+            }
+
+            on_stack.insert(*root);
+            let mut stack = vec![(*root, successors(*root), 0_usize)];
+
+            while let Some((state, state_successors, next_idx)) = stack.last_mut() {
+                let state = *state;
+
+                match state_successors.get(*next_idx).copied() {
+                    Some(next) => {
+                        *next_idx += 1;
+
+                        if on_stack.contains(&next) {
+                            return None;
+                        }
+
+                        if visited.insert(next) {
+                            on_stack.insert(next);
+                            stack.push((next, successors(next), 0));
+                        }
+                    }
+                    None => {
+                        on_stack.remove(&state);
+                        order.push(state);
+                        stack.pop();
+                    }
+                }
+            }
+        }
+
+        Some(order)
+    }
+
+    // Walks both automatons determinized on the fly in breadth-first order, so the
+    // first word found is the shortest one (and the lexicographically smallest among them)
+    fn find_distinguishing_word(
+        left: &Self,
+        right: &Self,
+        is_distinguishing: fn(bool, bool) -> bool,
+    ) -> Option<String> {
+        let alphabet: BTreeSet<char> = left
+            .get_alphabet()
+            .union(&right.get_alphabet())
+            .filter_map(|symbol| match symbol {
+                AutomatonTransition::Symbol(sym) => Some(*sym),
+                AutomatonTransition::Epsilon => None,
+            })
+            .collect();
+
+        let start = (
+            left.epsilon_closure(&left.start_states),
+            right.epsilon_closure(&right.start_states),
+        );
+
+        let mut pairs = Vec::<SubsetPair>::new();
+        let mut parents = Vec::<Option<(usize, char)>>::new();
+        let mut mapping = BTreeMap::<SubsetPair, usize>::new();
+        let mut queue = VecDeque::<usize>::from([0]);
+
+        mapping.insert(start.clone(), 0);
+        pairs.push(start);
+        parents.push(None);
+
+        while let Some(curr) = queue.pop_front() {
+            let (left_states, right_states) = pairs[curr].clone();
+
+            if is_distinguishing(
+                left.contains_accept(&left_states),
+                right.contains_accept(&right_states),
+            ) {
+                return Some(Self::restore_word(&parents, curr));
+            }
+
+            alphabet.iter().for_each(|sym| {
+                let symbol = AutomatonTransition::Symbol(*sym);
+                let next = (
+                    left.step(&left_states, symbol),
+                    right.step(&right_states, symbol),
+                );
+
+                if !mapping.contains_key(&next) {
+                    mapping.insert(next.clone(), pairs.len());
+                    queue.push_back(pairs.len());
+                    pairs.push(next);
+                    parents.push(Some((curr, *sym)));
+                }
+            });
+        }
+
+        None
+    }
+
+    pub(super) fn contains_accept(&self, states: &BTreeSet<AutomatonState>) -> bool {
+        states
+            .iter()
+            .any(|state| self.accept_states.contains(state))
+    }
+
+    pub(super) fn restore_word(parents: &[Option<(usize, char)>], mut curr: usize) -> String {
+        let mut word = Vec::<char>::new();
+
+        while let Some((parent, sym)) = parents[curr] {
+            word.push(sym);
+            curr = parent;
+        }
+
+        word.iter().rev().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finite_automaton::tests::from_string;
+
+    #[test]
+    fn equivalent_unit_1() {
+        let left = from_string("(a|b)*");
+        let right = from_string("(a*b*)*");
+
+        assert_eq!(
+            FiniteAutomaton::equivalent(&left, &right),
+            LanguageEquivalence::Equal
+        );
+
+        let mut right = right;
+        right.eliminate_epsilon();
+        let right = FiniteAutomaton::to_dfa(&right);
+
+        assert_eq!(
+            FiniteAutomaton::equivalent(&left, &right),
+            LanguageEquivalence::Equal
+        );
+    }
+
+    #[test]
+    fn equivalent_unit_2() {
+        let left = from_string("(a|b)*abb");
+        let right = from_string("(a|b)*b");
+
+        assert_eq!(
+            FiniteAutomaton::equivalent(&left, &right),
+            LanguageEquivalence::Counterexample("b".to_string())
+        );
+
+        let left = from_string("a+|b");
+        let right = from_string("a|b|aaa+");
+
+        assert_eq!(
+            FiniteAutomaton::equivalent(&left, &right),
+            LanguageEquivalence::Counterexample("aa".to_string())
+        );
+
+        // Different alphabets
+        assert_eq!(
+            FiniteAutomaton::equivalent(&from_string("a*"), &from_string("[ab]*")),
+            LanguageEquivalence::Counterexample("b".to_string())
+        );
+    }
+
+    #[test]
+    fn is_subset_of_unit_1() {
+        let tightened = from_string("[0-9]{4}");
+        let original = from_string("[0-9]+");
+
+        assert_eq!(
+            tightened.is_subset_of(&original),
+            LanguageInclusion::Included
+        );
+        assert_eq!(
+            original.is_subset_of(&tightened),
+            LanguageInclusion::Counterexample("0".to_string())
+        );
+    }
+
+    #[test]
+    fn is_subset_of_unit_2() {
+        let left = from_string("(ab)*|ba");
+        let right = from_string("(ab|b)*");
+
+        assert_eq!(
+            left.is_subset_of(&right),
+            LanguageInclusion::Counterexample("ba".to_string())
+        );
+
+        let empty = FiniteAutomaton::default();
+
+        assert_eq!(empty.is_subset_of(&left), LanguageInclusion::Included);
+        assert_eq!(
+            right.is_subset_of(&empty),
+            LanguageInclusion::Counterexample(String::new())
+        );
+    }
+
+    #[test]
+    fn is_empty_unit_1() {
+        assert!(FiniteAutomaton::default().is_empty());
+        assert!(!from_string("1").is_empty());
+        assert!(from_string("a & b").is_empty());
+        assert!(!from_string("a*b & (a|b)*b").is_empty());
+    }
+
+    #[test]
+    fn is_universal_unit_1() {
+        let alphabet = AutomatonAlphabet::from_iter("ab".chars().map(AutomatonTransition::Symbol));
+
+        assert!(from_string("(a|b)*").is_universal(&alphabet));
+        assert!(from_string("(a*b*)*").is_universal(&alphabet));
+        assert!(!from_string("(a|b)+").is_universal(&alphabet));
+        assert!(!from_string("a*").is_universal(&alphabet));
+    }
+
+    #[test]
+    fn is_finite_unit_1() {
+        assert!(from_string("a|bc|1").is_finite());
+        assert!(from_string("(a|b){3}").is_finite());
+        assert!(!from_string("ab*").is_finite());
+
+        // Epsilon cycles and loops which can't lead to acceptance don't count
+        assert!(from_string("(1)*a").is_finite());
+        assert!(from_string("a | b+ & c").is_finite());
+
+        assert_eq!(from_string("a|bc|1").len_if_finite(), Some(3));
+        assert_eq!(from_string("(a|b){3}").len_if_finite(), Some(8));
+        assert_eq!(from_string("(a|aa)(a|aa)").len_if_finite(), Some(3));
+        assert_eq!(from_string("ab*").len_if_finite(), None);
+        assert_eq!(FiniteAutomaton::default().len_if_finite(), Some(0));
+    }
+}
@@ -0,0 +1,724 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::{AutomatonState, AutomatonTransition, FiniteAutomaton, MinimizationAlgorithm};
+
+// The reachable part of a deterministic automaton with densely renumbered states.
+// States are numbered in breadth-first order, so the start state is always 0,
+// and every missing transition leads to an implicit sink numbered states.len()
+#[derive(Debug, Clone)]
+struct DenseAutomaton {
+    states: Vec<AutomatonState>,
+    symbols: Vec<AutomatonTransition>,
+    accept: Vec<bool>,
+    // delta[state * symbols.len() + symbol] is the destination state
+    delta: Vec<usize>,
+}
+
+// A partition of 0..n in which every class occupies a contiguous range of elements
+#[derive(Debug, Clone)]
+struct Partition {
+    elements: Vec<usize>,
+    position: Vec<usize>,
+    class_of: Vec<usize>,
+    first: Vec<usize>,
+    end: Vec<usize>,
+    // Marked elements of a class are kept at the beginning of its range
+    marked: Vec<usize>,
+}
+
+impl DenseAutomaton {
+    fn new(dfa: &FiniteAutomaton) -> Self {
+        let symbols: Vec<AutomatonTransition> = dfa
+            .get_alphabet()
+            .into_iter()
+            .filter(|symbol| *symbol != AutomatonTransition::Epsilon)
+            .collect();
+
+        let mut states: Vec<AutomatonState> = dfa.start_states.iter().copied().collect();
+        let mut mapping: BTreeMap<AutomatonState, usize> = states
+            .iter()
+            .enumerate()
+            .map(|(idx, state)| (*state, idx))
+            .collect();
+
+        let mut curr = 0;
+
+        while curr < states.len() {
+            // SAFETY: every state must have been created via
+            // new_state() and thus is present in transitions map
+            dfa.transitions[&states[curr]]
+                .iter()
+                .filter(|(symbol, _)| **symbol != AutomatonTransition::Epsilon)
+                .flat_map(|(_, dest_states)| dest_states.iter())
+                .for_each(|dest_state| {
+                    if !mapping.contains_key(dest_state) {
+                        mapping.insert(*dest_state, states.len());
+                        states.push(*dest_state);
+                    }
+                });
+
+            curr += 1;
+        }
+
+        let sink = states.len();
+        let mut delta = vec![sink; (states.len() + 1) * symbols.len()];
+
+        states.iter().enumerate().for_each(|(idx, state)| {
+            symbols.iter().enumerate().for_each(|(symbol_idx, symbol)| {
+                let dest_state = dfa.transitions[state]
+                    .get(symbol)
+                    .and_then(|dest_states| dest_states.first());
+
+                if let Some(dest_state) = dest_state {
+                    delta[idx * symbols.len() + symbol_idx] = mapping[dest_state];
+                }
+            });
+        });
+
+        Self {
+            accept: states
+                .iter()
+                .map(|state| dfa.accept_states.contains(state))
+                .chain([false])
+                .collect(),
+            states,
+            symbols,
+            delta,
+        }
+    }
+
+    // Including the implicit sink
+    fn states_num(&self) -> usize {
+        self.states.len() + 1
+    }
+
+    fn next(&self, state: usize, symbol_idx: usize) -> usize {
+        self.delta[state * self.symbols.len() + symbol_idx]
+    }
+
+    // Merges the states of every class into one. The class of the implicit sink
+    // is dropped unless some actual state has been merged into it
+    fn quotient(&self, class_of: &[usize]) -> FiniteAutomaton {
+        let mut automaton = FiniteAutomaton::default();
+        automaton.set_alphabet(&self.symbols.iter().copied().collect());
+        let sink = self.states.len();
+        let sink_class = class_of[sink];
+        let keep_sink_class = class_of[..sink].contains(&sink_class);
+
+        let mut mapping = BTreeMap::<usize, AutomatonState>::new();
+
+        class_of[..sink].iter().for_each(|class| {
+            if !mapping.contains_key(class) && (*class != sink_class || keep_sink_class) {
+                mapping.insert(*class, automaton.add_state());
+            }
+        });
+
+        if sink > 0 {
+            automaton.start_states.insert(mapping[&class_of[0]]);
+        }
+
+        (0..sink).for_each(|state| {
+            let class_state = mapping[&class_of[state]];
+
+            automaton
+                .origins
+                .entry(class_state)
+                .or_default()
+                .push(self.states[state]);
+
+            if self.accept[state] {
+                automaton.accept_states.insert(class_state);
+            }
+
+            self.symbols
+                .iter()
+                .enumerate()
+                .for_each(|(symbol_idx, symbol)| {
+                    let dest_state = self.next(state, symbol_idx);
+
+                    if dest_state != sink {
+                        automaton.add_transition(
+                            class_state,
+                            *symbol,
+                            mapping[&class_of[dest_state]],
+                        );
+                    }
+                });
+        });
+
+        automaton
+    }
+}
+
+impl Partition {
+    // Accepting and non-accepting elements initially form two classes
+    fn new(accept: &[bool]) -> Self {
+        let mut elements: Vec<usize> = (0..accept.len()).collect();
+        elements.sort_by_key(|element| !accept[*element]);
+
+        let mut position = vec![0; accept.len()];
+        elements
+            .iter()
+            .enumerate()
+            .for_each(|(pos, element)| position[*element] = pos);
+
+        let accept_num = accept.iter().filter(|accept| **accept).count();
+        let mut partition = Self {
+            elements,
+            position,
+            class_of: vec![0; accept.len()],
+            first: vec![0],
+            end: vec![accept.len()],
+            marked: vec![0],
+        };
+
+        if accept_num > 0 && accept_num < accept.len() {
+            (0..accept_num).for_each(|pos| partition.mark(partition.elements[pos]));
+            partition.split(0);
+        }
+
+        partition
+    }
+
+    fn classes_num(&self) -> usize {
+        self.first.len()
+    }
+
+    fn class_len(&self, class: usize) -> usize {
+        self.end[class] - self.first[class]
+    }
+
+    fn class(&self, class: usize) -> &[usize] {
+        &self.elements[self.first[class]..self.end[class]]
+    }
+
+    fn is_marked(&self, element: usize) -> bool {
+        let class = self.class_of[element];
+        self.position[element] < self.first[class] + self.marked[class]
+    }
+
+    fn mark(&mut self, element: usize) {
+        if self.is_marked(element) {
+            return;
+        }
+
+        let class = self.class_of[element];
+        let pos = self.position[element];
+        let marked_pos = self.first[class] + self.marked[class];
+        let other = self.elements[marked_pos];
+
+        self.elements.swap(pos, marked_pos);
+        self.position[element] = marked_pos;
+        self.position[other] = pos;
+        self.marked[class] += 1;
+    }
+
+    // Moves the marked elements of the class into a new class and returns it,
+    // nothing happens if either all or none of the elements have been marked
+    fn split(&mut self, class: usize) -> Option<usize> {
+        let marked = std::mem::take(&mut self.marked[class]);
+
+        if marked == 0 || marked == self.class_len(class) {
+            return None;
+        }
+
+        let new_class = self.classes_num();
+        let first = self.first[class];
+
+        self.first.push(first);
+        self.end.push(first + marked);
+        self.marked.push(0);
+        self.first[class] = first + marked;
+
+        self.elements[first..first + marked]
+            .iter()
+            .for_each(|element| self.class_of[*element] = new_class);
+
+        Some(new_class)
+    }
+}
+
+impl FiniteAutomaton {
+    // Nondeterministic automatons get determinized first. The result only has
+    // reachable states numbered from 0, and a sink is only kept if the input had one
+    pub fn make_minimal(&mut self) {
+        self.minimize_with(MinimizationAlgorithm::default());
+    }
+
+    // Brzozowski's algorithm works on nfas directly and never keeps a sink
+    // since reversal drops all the states from which acceptance can't be reached
+    pub fn minimize_with(&mut self, algorithm: MinimizationAlgorithm) {
+        let alphabet = self.get_alphabet();
+        let auto_trim = self.auto_trim;
+        let track_origins = self.track_origins;
+
+        *self = match algorithm {
+            MinimizationAlgorithm::Brzozowski => {
+                // The subsets refer to the states of the reversed automaton, so they are useless
+                let mut minimal = Self::to_dfa(&Self::to_dfa(&self.reverse()).reverse());
+                minimal.origins.clear();
+                minimal
+            }
+            MinimizationAlgorithm::Hopcroft | MinimizationAlgorithm::Moore => {
+                let is_deterministic = self.is_deterministic();
+                let dfa = self.determinized();
+                let dense = DenseAutomaton::new(&dfa);
+
+                let mut minimal = match algorithm {
+                    MinimizationAlgorithm::Moore => Self::moore(&dense),
+                    _ => Self::hopcroft(&dense),
+                };
+
+                // Origins of the classes have to refer to the states of the original automaton
+                // rather than to the ones of its determinized version
+                if track_origins && !is_deterministic {
+                    minimal.origins.values_mut().for_each(|origin| {
+                        let nfa_states: BTreeSet<AutomatonState> = origin
+                            .iter()
+                            .flat_map(|dfa_state| dfa.state_origin(*dfa_state))
+                            .copied()
+                            .collect();
+
+                        *origin = nfa_states.into_iter().collect();
+                    });
+                }
+
+                minimal
+            }
+        };
+
+        if !track_origins {
+            self.origins.clear();
+        }
+
+        self.alphabet = alphabet;
+        self.auto_trim = auto_trim;
+        self.track_origins = track_origins;
+
+        if self.auto_trim {
+            self.trim();
+        }
+    }
+
+    // Refines the partition by the classes of successors until it stops changing
+    fn moore(dense: &DenseAutomaton) -> Self {
+        let states_num = dense.states_num();
+        let symbols_num = dense.symbols.len();
+
+        let mut class_of: Vec<usize> = dense.accept.iter().map(|accept| *accept as usize).collect();
+        let mut classes_num = 0;
+
+        loop {
+            let mut signatures = BTreeMap::<Vec<usize>, usize>::new();
+
+            let next_class_of: Vec<usize> = (0..states_num)
+                .map(|state| {
+                    let signature: Vec<usize> = [class_of[state]]
+                        .into_iter()
+                        .chain(
+                            (0..symbols_num)
+                                .map(|symbol_idx| class_of[dense.next(state, symbol_idx)]),
+                        )
+                        .collect();
+
+                    let next_class = signatures.len();
+                    *signatures.entry(signature).or_insert(next_class)
+                })
+                .collect();
+
+            class_of = next_class_of;
+
+            if signatures.len() == classes_num {
+                break;
+            }
+
+            classes_num = signatures.len();
+        }
+
+        dense.quotient(&class_of)
+    }
+
+    fn hopcroft(dense: &DenseAutomaton) -> Self {
+        let states_num = dense.states_num();
+        let symbols_num = dense.symbols.len();
+
+        // Transitions inverted into lists of predecessors for every (symbol, state)
+        let mut predecessors = vec![Vec::<usize>::new(); states_num * symbols_num];
+
+        (0..states_num).for_each(|state| {
+            (0..symbols_num).for_each(|symbol_idx| {
+                let dest_state = dense.next(state, symbol_idx);
+                predecessors[symbol_idx * states_num + dest_state].push(state);
+            });
+        });
+
+        let mut partition = Partition::new(&dense.accept);
+        let mut queue = VecDeque::<(usize, usize)>::new();
+        let mut queued = vec![false; partition.classes_num() * symbols_num];
+
+        // It is enough to split by the smaller of the two initial classes
+        let smaller = (0..partition.classes_num())
+            .min_by_key(|class| partition.class_len(*class))
+            .filter(|_| partition.classes_num() > 1);
+
+        if let Some(class) = smaller {
+            (0..symbols_num).for_each(|symbol_idx| {
+                queue.push_back((class, symbol_idx));
+                queued[class * symbols_num + symbol_idx] = true;
+            });
+        }
+
+        while let Some((splitter, symbol_idx)) = queue.pop_front() {
+            queued[splitter * symbols_num + symbol_idx] = false;
+
+            let mut touched = Vec::<usize>::new();
+            let splitter_states = partition.class(splitter).to_vec();
+
+            splitter_states.iter().for_each(|dest_state| {
+                predecessors[symbol_idx * states_num + dest_state]
+                    .iter()
+                    .for_each(|state| {
+                        let class = partition.class_of[*state];
+
+                        if partition.marked[class] == 0 {
+                            touched.push(class);
+                        }
+
+                        partition.mark(*state);
+                    });
+            });
+
+            for class in touched {
+                let Some(new_class) = partition.split(class) else {
+                    continue;
+                };
+
+                queued.resize(partition.classes_num() * symbols_num, false);
+
+                // If the old class is already queued both halves have to be processed,
+                // otherwise the smaller half is enough
+                (0..symbols_num).for_each(|symbol_idx| {
+                    let next_splitter = match queued[class * symbols_num + symbol_idx] {
+                        true => new_class,
+                        false if partition.class_len(new_class) <= partition.class_len(class) => {
+                            new_class
+                        }
+                        false => class,
+                    };
+
+                    queue.push_back((next_splitter, symbol_idx));
+                    queued[next_splitter * symbols_num + symbol_idx] = true;
+                });
+            }
+        }
+
+        dense.quotient(&partition.class_of)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::time::Instant;
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::{finite_automaton::tests::from_string, LanguageEquivalence};
+
+    // A complete dfa in which every state is reachable from the start
+    fn random_dfa(states_num: usize, alphabet: &str, rng: &mut StdRng) -> FiniteAutomaton {
+        let mut dfa = FiniteAutomaton::default();
+        let states: Vec<AutomatonState> = (0..states_num).map(|_| dfa.add_state()).collect();
+        dfa.start_states.insert(states[0]);
+
+        states.iter().for_each(|state| {
+            if rng.gen_bool(0.3) {
+                dfa.accept_states.insert(*state);
+            }
+        });
+
+        let symbols: Vec<char> = alphabet.chars().collect();
+        let mut has_transition = BTreeSet::<(usize, char)>::new();
+
+        // A spanning tree makes every state reachable
+        (1..states_num).for_each(|state| loop {
+            let parent = rng.gen_range(0..state);
+            let sym = symbols[rng.gen_range(0..symbols.len())];
+
+            if has_transition.insert((parent, sym)) {
+                dfa.add_transition(
+                    states[parent],
+                    AutomatonTransition::Symbol(sym),
+                    states[state],
+                );
+                break;
+            }
+        });
+
+        (0..states_num).for_each(|state| {
+            symbols.iter().for_each(|sym| {
+                if has_transition.insert((state, *sym)) {
+                    let dest_state = states[rng.gen_range(0..states_num)];
+                    dfa.add_transition(
+                        states[state],
+                        AutomatonTransition::Symbol(*sym),
+                        dest_state,
+                    );
+                }
+            });
+        });
+
+        dfa
+    }
+
+    // The partition refinement make_minimal used to be implemented with,
+    // kept as a reference for the tests and the benchmark
+    fn partition_refinement(automaton: &mut FiniteAutomaton) {
+        let mut queue = VecDeque::<(BTreeSet<AutomatonState>, AutomatonTransition)>::new();
+        let allphabet = automaton.get_alphabet();
+        let accept_class = automaton.accept_states.clone();
+        let non_accept_class: BTreeSet<_> = automaton
+            .transitions
+            .keys()
+            .copied()
+            .filter(|state| !automaton.accept_states.contains(state))
+            .collect();
+
+        allphabet.iter().for_each(|sym| {
+            queue.push_back((accept_class.clone(), *sym));
+            queue.push_back((non_accept_class.clone(), *sym));
+        });
+
+        let mut partition =
+            BTreeSet::<BTreeSet<AutomatonState>>::from([accept_class, non_accept_class]);
+
+        while let Some((splitter, symbol)) = queue.pop_front() {
+            partition.clone().iter().for_each(|class| {
+                let (splitter_reachable, splitter_unreachable): (BTreeSet<AutomatonState>, _) =
+                    class.iter().partition(|state| {
+                        automaton.transitions[*state]
+                            .get(&symbol)
+                            .unwrap_or(&BTreeSet::<AutomatonState>::new())
+                            .iter()
+                            .any(|dest_state| splitter.contains(dest_state))
+                    });
+
+                if !splitter_reachable.is_empty() && !splitter_unreachable.is_empty() {
+                    allphabet.iter().for_each(|sym| {
+                        queue.push_back((splitter_reachable.clone(), *sym));
+                        queue.push_back((splitter_unreachable.clone(), *sym));
+                    });
+
+                    partition.remove(class);
+                    partition.insert(splitter_reachable);
+                    partition.insert(splitter_unreachable);
+                }
+            });
+        }
+
+        let mut state_to_class_state = BTreeMap::<AutomatonState, AutomatonState>::new();
+        let mut class_to_state = BTreeMap::<BTreeSet<AutomatonState>, AutomatonState>::new();
+
+        partition.iter().for_each(|class| {
+            let new_state = automaton.add_state();
+            class_to_state.insert(class.clone(), new_state);
+
+            class.iter().for_each(|state| {
+                state_to_class_state.insert(*state, new_state);
+            });
+        });
+
+        automaton
+            .accept_states
+            .clone()
+            .iter()
+            .for_each(|accept_state| {
+                automaton
+                    .accept_states
+                    .insert(state_to_class_state[accept_state]);
+            });
+
+        automaton
+            .start_states
+            .clone()
+            .iter()
+            .for_each(|start_state| {
+                automaton
+                    .start_states
+                    .insert(state_to_class_state[start_state]);
+            });
+
+        partition.iter().for_each(|class| {
+            let class_state = class_to_state[class];
+
+            class.iter().for_each(|old_state| {
+                automaton.transitions[old_state].clone().iter().for_each(
+                    |(symbol, symbol_transitions)| {
+                        symbol_transitions.iter().for_each(|symbol_transition| {
+                            automaton.add_transition(
+                                class_state,
+                                *symbol,
+                                state_to_class_state[symbol_transition],
+                            );
+                        });
+                    },
+                );
+
+                automaton.remove_state(*old_state);
+            });
+        });
+    }
+
+    #[test]
+    fn make_minimal_unit_1() {
+        let mut dfa = from_string("(a|b)*abb");
+        dfa.make_minimal();
+
+        assert_eq!(dfa.transitions.len(), 4);
+        assert_eq!(dfa.start_states, BTreeSet::from([0]));
+        assert!(dfa.is_deterministic());
+        assert_eq!(
+            FiniteAutomaton::equivalent(&dfa, &from_string("(a|b)*abb")),
+            LanguageEquivalence::Equal
+        );
+
+        // The sink is only kept if there was one in the first place
+        let mut dfa = FiniteAutomaton::to_dfa(&from_string("a*b*").without_epsilon());
+        dfa.make_minimal();
+        assert_eq!(dfa.transitions.len(), 2);
+
+        dfa.make_full();
+        dfa.make_minimal();
+        assert_eq!(dfa.transitions.len(), 3);
+    }
+
+    #[test]
+    fn make_minimal_unit_2() {
+        let mut empty = FiniteAutomaton::default();
+        empty.make_minimal();
+        assert_eq!(empty.transitions.len(), 0);
+
+        let mut epsilon = from_string("1");
+        epsilon.make_minimal();
+        assert_eq!(epsilon.transitions.len(), 1);
+        assert_eq!(epsilon.accept_states, BTreeSet::from([0]));
+
+        let mut nothing = from_string("a & b");
+        nothing.make_minimal();
+        assert!(nothing.accept_states.is_empty());
+    }
+
+    #[test]
+    fn make_minimal_unit_3() {
+        let mut rng = StdRng::seed_from_u64(15);
+
+        for _ in 0..20 {
+            let dfa = random_dfa(40, "abc", &mut rng);
+
+            let mut expected = dfa.clone();
+            partition_refinement(&mut expected);
+
+            let mut minimal = dfa.clone();
+            minimal.make_minimal();
+
+            assert_eq!(minimal.transitions.len(), expected.transitions.len());
+            assert_eq!(
+                FiniteAutomaton::equivalent(&minimal, &dfa),
+                LanguageEquivalence::Equal
+            );
+        }
+    }
+
+    #[test]
+    fn minimize_with_unit_1() {
+        let algorithms = [
+            MinimizationAlgorithm::Hopcroft,
+            MinimizationAlgorithm::Moore,
+            MinimizationAlgorithm::Brzozowski,
+        ];
+
+        for expr in [
+            "(a|b)*abb",
+            "a((ba)*a(ab)* | a)*",
+            "(a|b)*a(a|b){3}",
+            "1",
+            "a & b",
+        ] {
+            let nfa = from_string(expr);
+
+            let minimal: Vec<FiniteAutomaton> = algorithms
+                .iter()
+                .map(|algorithm| {
+                    let mut automaton = nfa.clone();
+                    automaton.minimize_with(*algorithm);
+                    automaton
+                })
+                .collect();
+
+            // Moore and Hopcroft only differ in the way they refine the partition
+            assert_eq!(minimal[0].transitions, minimal[1].transitions);
+
+            minimal.iter().for_each(|automaton| {
+                assert!(automaton.is_deterministic());
+                assert_eq!(automaton.transitions.len(), minimal[0].transitions.len());
+                assert_eq!(
+                    FiniteAutomaton::equivalent(automaton, &nfa),
+                    LanguageEquivalence::Equal
+                );
+            });
+        }
+    }
+
+    #[test]
+    fn minimize_with_unit_2() {
+        let mut rng = StdRng::seed_from_u64(16);
+
+        // Reversed random dfas blow up on determinization, so they have to be small
+        for _ in 0..20 {
+            let dfa = random_dfa(12, "ab", &mut rng);
+
+            let mut hopcroft = dfa.clone();
+            hopcroft.minimize_with(MinimizationAlgorithm::Hopcroft);
+
+            let mut moore = dfa.clone();
+            moore.minimize_with(MinimizationAlgorithm::Moore);
+
+            let mut brzozowski = dfa.clone();
+            brzozowski.minimize_with(MinimizationAlgorithm::Brzozowski);
+
+            // The input is complete, so only Hopcroft and Moore may keep a sink
+            assert!(hopcroft.transitions.len() <= brzozowski.transitions.len() + 1);
+
+            assert_eq!(hopcroft.transitions, moore.transitions);
+            assert_eq!(
+                FiniteAutomaton::equivalent(&brzozowski, &dfa),
+                LanguageEquivalence::Equal
+            );
+        }
+    }
+
+    // cargo test --release make_minimal_bench -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn make_minimal_bench() {
+        let mut rng = StdRng::seed_from_u64(5000);
+
+        for states_num in [250, 500, 1000, 2000] {
+            let dfa = random_dfa(states_num, "abcd", &mut rng);
+
+            let mut expected = dfa.clone();
+            let timer = Instant::now();
+            partition_refinement(&mut expected);
+            let reference_time = timer.elapsed();
+
+            let mut minimal = dfa.clone();
+            let timer = Instant::now();
+            minimal.make_minimal();
+            let hopcroft_time = timer.elapsed();
+
+            assert_eq!(minimal.transitions.len(), expected.transitions.len());
+            println!("{states_num} states: partition refinement {reference_time:?}, hopcroft {hopcroft_time:?}");
+        }
+    }
+}
@@ -1,38 +1,108 @@
+mod bitset;
+mod builder;
+mod counting;
+mod determinization;
+mod graph;
+mod isomorphism;
+mod language;
+mod minimization;
+mod product;
+mod query;
+mod sampling;
+mod typed;
+mod words;
+
 use std::{
-    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    collections::{BTreeMap, BTreeSet},
+    fmt,
     fs::File,
     io::{self, BufWriter, Write},
     ops::Deref,
     process::Command,
 };
 
+use bitset::BitSet;
+use graph::strongly_connected_components;
 use tabbycat::attributes::*;
 use tabbycat::{AttrList, AttrType, Edge, GraphBuilder, GraphType, Identity, StmtList};
 
 use super::{
-    AutomatonAlphabet, AutomatonState, AutomatonTransition, AutomatonTransitionList,
-    FiniteAutomaton, Regex, RegexEntry, RegexOps,
+    AutomatonAlphabet, AutomatonState, AutomatonTransition, AutomatonTransitionList, DumpOptions,
+    FiniteAutomaton, FiniteAutomatonBuilder, FromRegexError, Regex, RegexEntry, RegexOps,
 };
 
+impl DumpOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Label the states with their origins instead of their numbers
+    pub fn with_origins(mut self, origins: bool) -> Self {
+        self.origins = origins;
+        self
+    }
+
+    // Highlight the states and transitions the run on the word goes through
+    pub fn with_trace(mut self, word: &str) -> Self {
+        self.trace = Some(word.to_string());
+        self
+    }
+}
+
+impl fmt::Display for FromRegexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UndeclaredAlphabet => write!(
+                f,
+                "Negated classes, wildcards and complements need a declared alphabet"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FromRegexError {}
+
 impl FiniteAutomaton {
     pub fn from_regex(regex: &Regex) -> Self {
+        Self::try_from_regex(regex).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    // The alphabet is made of the symbols the regex mentions, so regexes which would have
+    // to be resolved against some other symbols are rejected, see from_regex_with_alphabet()
+    pub fn try_from_regex(regex: &Regex) -> Result<Self, FromRegexError> {
+        match regex.needs_alphabet() {
+            true => Err(FromRegexError::UndeclaredAlphabet),
+            false => Ok(Self::from_regex_with_alphabet(regex, &regex.get_alphabet())),
+        }
+    }
+
+    // Negated classes and wildcards match the symbols of the given alphabet
+    pub fn from_regex_with_alphabet(regex: &Regex, alphabet: &AutomatonAlphabet) -> Self {
         match regex.root.as_ref() {
-            Some(root) => {
+            Some(root) => Self::from_regex_entry(root, alphabet),
+            None => {
                 let mut nfa = Self::default();
-                let start_state = nfa.add_state();
-                nfa.start_states = BTreeSet::from([start_state]);
-                let accept_state = nfa.add_state();
-                nfa.accept_states.insert(accept_state);
-                nfa.traverse_regex(&root, start_state, accept_state);
+                nfa.set_alphabet(alphabet);
                 nfa
             }
-            None => Self::default(),
         }
     }
 
+    fn from_regex_entry(root: &RegexEntry, alphabet: &AutomatonAlphabet) -> Self {
+        let mut nfa = Self::default();
+        nfa.set_alphabet(alphabet);
+        let start_state = nfa.add_state();
+        nfa.start_states = BTreeSet::from([start_state]);
+        let accept_state = nfa.add_state();
+        nfa.accept_states.insert(accept_state);
+        nfa.traverse_regex(root, alphabet, start_state, accept_state);
+        nfa
+    }
+
     fn traverse_regex(
         &mut self,
         curr_op: &RegexEntry,
+        alphabet: &AutomatonAlphabet,
         start_state: AutomatonState,
         accept_state: AutomatonState,
     ) {
@@ -42,18 +112,31 @@ impl FiniteAutomaton {
                 let left_accept = self.add_state();
                 self.add_transition(start_state, AutomatonTransition::Epsilon, left_start);
                 self.add_transition(left_accept, AutomatonTransition::Epsilon, accept_state);
-                self.traverse_regex(left, left_start, left_accept);
+                self.traverse_regex(left, alphabet, left_start, left_accept);
 
                 let right_start = self.add_state();
                 let right_accept = self.add_state();
                 self.add_transition(start_state, AutomatonTransition::Epsilon, right_start);
                 self.add_transition(right_accept, AutomatonTransition::Epsilon, accept_state);
-                self.traverse_regex(right, right_start, right_accept);
+                self.traverse_regex(right, alphabet, right_start, right_accept);
+            }
+            RegexOps::Intersection(left, right) => {
+                let product = Self::intersect(
+                    &Self::from_regex_entry(left, alphabet),
+                    &Self::from_regex_entry(right, alphabet),
+                );
+
+                self.embed(&product, start_state, accept_state);
+            }
+            RegexOps::Complement(what) => {
+                let mut dfa = Self::to_dfa(&Self::from_regex_entry(what, alphabet));
+                dfa.make_complement();
+                self.embed(&dfa, start_state, accept_state);
             }
             RegexOps::Consecutive(left, right) => {
                 let inbetween = self.add_state();
-                self.traverse_regex(left, start_state, inbetween);
-                self.traverse_regex(right, inbetween, accept_state);
+                self.traverse_regex(left, alphabet, start_state, inbetween);
+                self.traverse_regex(right, alphabet, inbetween, accept_state);
             }
             RegexOps::NoneOrMore(what) => {
                 let repeat_start = self.add_state();
@@ -62,7 +145,7 @@ impl FiniteAutomaton {
                 self.add_transition(start_state, AutomatonTransition::Epsilon, accept_state);
                 self.add_transition(repeat_accept, AutomatonTransition::Epsilon, accept_state);
                 self.add_transition(repeat_accept, AutomatonTransition::Epsilon, repeat_start);
-                self.traverse_regex(what, repeat_start, repeat_accept);
+                self.traverse_regex(what, alphabet, repeat_start, repeat_accept);
             }
             RegexOps::NoneOrOnce(what) => {
                 let repeat_start = self.add_state();
@@ -70,7 +153,7 @@ impl FiniteAutomaton {
                 self.add_transition(start_state, AutomatonTransition::Epsilon, repeat_start);
                 self.add_transition(start_state, AutomatonTransition::Epsilon, accept_state);
                 self.add_transition(repeat_accept, AutomatonTransition::Epsilon, accept_state);
-                self.traverse_regex(what, repeat_start, repeat_accept);
+                self.traverse_regex(what, alphabet, repeat_start, repeat_accept);
             }
             RegexOps::OnceOrMore(what) => {
                 let repeat_start = self.add_state();
@@ -78,11 +161,75 @@ impl FiniteAutomaton {
                 self.add_transition(start_state, AutomatonTransition::Epsilon, repeat_start);
                 self.add_transition(repeat_accept, AutomatonTransition::Epsilon, accept_state);
                 self.add_transition(repeat_accept, AutomatonTransition::Epsilon, repeat_start);
-                self.traverse_regex(what, repeat_start, repeat_accept);
+                self.traverse_regex(what, alphabet, repeat_start, repeat_accept);
+            }
+            RegexOps::Repeat(what, min, max) => {
+                // Unroll the mandatory part first: what{min}
+                let mut curr_state = start_state;
+
+                for _ in 0..*min {
+                    let next_state = self.add_state();
+                    self.traverse_regex(what, alphabet, curr_state, next_state);
+                    curr_state = next_state;
+                }
+
+                // Then either what* or (what?){max - min}
+                match max {
+                    Some(max) => {
+                        for _ in *min..*max {
+                            let next_state = self.add_state();
+                            self.add_transition(
+                                curr_state,
+                                AutomatonTransition::Epsilon,
+                                accept_state,
+                            );
+                            self.traverse_regex(what, alphabet, curr_state, next_state);
+                            curr_state = next_state;
+                        }
+
+                        self.add_transition(curr_state, AutomatonTransition::Epsilon, accept_state);
+                    }
+                    None => {
+                        let repeat = Box::new(RegexOps::NoneOrMore(what.clone()));
+                        self.traverse_regex(&repeat, alphabet, curr_state, accept_state);
+                    }
+                }
             }
             RegexOps::Symbol(sym) => {
                 self.add_transition(start_state, AutomatonTransition::Symbol(*sym), accept_state);
             }
+            RegexOps::Class(ranges) => {
+                ranges.iter().for_each(|(first, last)| {
+                    (*first..=*last).for_each(|sym| {
+                        self.add_transition(
+                            start_state,
+                            AutomatonTransition::Symbol(sym),
+                            accept_state,
+                        );
+                    });
+                });
+            }
+            RegexOps::NegatedClass(ranges) => {
+                alphabet
+                    .iter()
+                    .filter(|symbol| match symbol {
+                        AutomatonTransition::Symbol(sym) => !ranges
+                            .iter()
+                            .any(|(first, last)| (first..=last).contains(&sym)),
+                        AutomatonTransition::Epsilon => false,
+                    })
+                    .for_each(|symbol| {
+                        self.add_transition(start_state, *symbol, accept_state);
+                    });
+            }
+            RegexOps::AnySymbol => {
+                alphabet
+                    .iter()
+                    .filter(|symbol| **symbol != AutomatonTransition::Epsilon)
+                    .for_each(|symbol| {
+                        self.add_transition(start_state, *symbol, accept_state);
+                    });
+            }
             RegexOps::Epsilon => {
                 self.add_transition(start_state, AutomatonTransition::Epsilon, accept_state);
             }
@@ -90,75 +237,42 @@ impl FiniteAutomaton {
     }
 
     pub fn eliminate_epsilon(&mut self) {
-        // Step 1: Create an epsilon closure
-        let mut closure_matrix = BTreeMap::<AutomatonState, BTreeMap<AutomatonState, bool>>::new();
-
-        self.transitions
-            .iter()
-            .for_each(|(state, state_transitions)| {
-                state_transitions
-                    .get(&AutomatonTransition::Epsilon)
-                    .unwrap_or(&BTreeSet::<AutomatonState>::new())
-                    .iter()
-                    .for_each(|epsilon_transition| {
-                        closure_matrix
-                            .entry(*state)
-                            .or_default()
-                            .insert(*epsilon_transition, true);
-                    });
-            });
-
-        self.floyd_warshall(&mut closure_matrix);
+        // Step 1: Compute the epsilon closure of every state
+        let states: Vec<AutomatonState> = self.transitions.keys().copied().collect();
+        let (component, closures) = self.epsilon_closures(&states);
+
+        // Step 2: Collect new accept states and (u - Epsilon - v - Symbol - w) edges
+        let mut new_accept_states = Vec::<AutomatonState>::new();
+        let mut new_transitions =
+            Vec::<(AutomatonState, AutomatonTransition, AutomatonState)>::new();
+
+        states.iter().enumerate().for_each(|(idx, state)| {
+            closures[component[idx]]
+                .iter()
+                .filter(|closure_idx| *closure_idx != idx)
+                .map(|closure_idx| states[closure_idx])
+                .for_each(|epsilon_state| {
+                    if self.accept_states.contains(&epsilon_state) {
+                        new_accept_states.push(*state);
+                    }
 
-        closure_matrix
-            .iter()
-            .for_each(|(from, epsilon_transitions)| {
-                epsilon_transitions.iter().for_each(|(to, reachable)| {
-                    if *reachable {
-                        self.add_transition(*from, AutomatonTransition::Epsilon, *to);
-                    };
+                    self.transitions[&epsilon_state]
+                        .iter()
+                        .filter(|(symbol, _)| **symbol != AutomatonTransition::Epsilon)
+                        .for_each(|(symbol, dest_states)| {
+                            dest_states.iter().for_each(|dest_state| {
+                                new_transitions.push((*state, *symbol, *dest_state));
+                            });
+                        });
                 });
-            });
+        });
 
-        // Step 2: Update accept states
-        self.transitions
-            .iter()
-            .for_each(|(state, state_transitions)| {
-                state_transitions
-                    .get(&AutomatonTransition::Epsilon)
-                    .unwrap_or(&BTreeSet::<AutomatonState>::new())
-                    .iter()
-                    .for_each(|epsilon_state| {
-                        if self.accept_states.contains(epsilon_state) {
-                            self.accept_states.insert(*state);
-                        }
-                    });
-            });
+        // Step 3: Update accept states and add the collected edges
+        self.accept_states.extend(new_accept_states);
 
-        // Step 3: Add (u - Epsilon - v - Symbol - w) edges
-        let cloned_transitions = self.transitions.clone();
-
-        cloned_transitions
-            .iter()
-            .for_each(|(state, state_transitions)| {
-                state_transitions
-                    .get(&AutomatonTransition::Epsilon)
-                    .unwrap_or(&BTreeSet::<AutomatonState>::new())
-                    .iter()
-                    .for_each(|epsilon_transition| {
-                        // SAFETY: every state must have been created via
-                        // new_state() and thus is present in transitions map
-                        cloned_transitions
-                            .get(epsilon_transition)
-                            .unwrap()
-                            .iter()
-                            .for_each(|(symbol, dest_states)| {
-                                dest_states.iter().for_each(|dest_state| {
-                                    self.add_transition(*state, *symbol, *dest_state);
-                                });
-                            });
-                    });
-            });
+        new_transitions
+            .into_iter()
+            .for_each(|(from, symbol, to)| self.add_transition(from, symbol, to));
 
         // Step 4: Remove all the epsilon transitions from the automaton
         self.transitions.values_mut().for_each(|state_transitions| {
@@ -167,22 +281,61 @@ impl FiniteAutomaton {
 
         // Step 5: Remove the dead states that could have appeared
         self.eliminate_dead();
+
+        if self.auto_trim {
+            self.trim();
+        }
     }
 
-    fn floyd_warshall(
-        &self,
-        matrix: &mut BTreeMap<AutomatonState, BTreeMap<AutomatonState, bool>>,
-    ) {
-        self.transitions.keys().for_each(|k| {
-            self.transitions.keys().for_each(|i| {
-                self.transitions.keys().for_each(|j| {
-                    let matrix_i_k = *matrix.entry(*i).or_default().entry(*k).or_default();
-                    let matrix_k_j = *matrix.entry(*k).or_default().entry(*j).or_default();
-                    let entry_i_j = matrix.entry(*i).or_default().entry(*j).or_default();
-                    *entry_i_j = *entry_i_j | (matrix_i_k & matrix_k_j);
-                });
+    // States of an epsilon cycle share the same closure, so the closures are computed
+    // once per strongly connected component with successors going first.
+    // Returns the component of every state and the closure of every component
+    fn epsilon_closures(&self, states: &[AutomatonState]) -> (Vec<usize>, Vec<BitSet>) {
+        let mapping: BTreeMap<AutomatonState, usize> = states
+            .iter()
+            .enumerate()
+            .map(|(idx, state)| (*state, idx))
+            .collect();
+
+        let adjacency: Vec<Vec<usize>> = states
+            .iter()
+            .map(|state| {
+                self.transitions[state]
+                    .get(&AutomatonTransition::Epsilon)
+                    .into_iter()
+                    .flatten()
+                    .map(|epsilon_state| mapping[epsilon_state])
+                    .collect()
+            })
+            .collect();
+
+        let component = strongly_connected_components(&adjacency);
+        let components_num = component.iter().max().map_or(0, |max| max + 1);
+
+        let mut members = vec![Vec::<usize>::new(); components_num];
+        component
+            .iter()
+            .enumerate()
+            .for_each(|(idx, comp)| members[*comp].push(idx));
+
+        let mut closures = Vec::<BitSet>::with_capacity(components_num);
+
+        (0..components_num).for_each(|comp| {
+            let mut closure = BitSet::new(states.len());
+
+            members[comp].iter().for_each(|member| {
+                closure.insert(*member);
+
+                adjacency[*member]
+                    .iter()
+                    .filter(|next| component[**next] != comp)
+                    .for_each(|next| closure.union_with(&closures[component[*next]]));
             });
+
+            closures.push(closure);
         });
+
+        (component, closures)
     }
 
     pub fn eliminate_dead(&mut self) {
@@ -213,89 +366,124 @@ impl FiniteAutomaton {
             });
     }
 
-    pub fn to_dfa(nfa: &FiniteAutomaton) -> Self {
-        let mut dfa = Self::default();
-        let mut queue = VecDeque::<AutomatonState>::new();
-        let mut used = HashSet::<AutomatonState>::new();
-        let mut mapping = HashMap::<AutomatonState, BTreeSet<AutomatonState>>::new();
-        let mut reverse_mapping = HashMap::<BTreeSet<AutomatonState>, AutomatonState>::new();
+    // Unlike eliminate_dead this also removes cycles of unreachable states
+    // and the states from which no accept state can be reached. The start states
+    // of an empty language are kept, so that it still can be completed
+    pub fn trim(&mut self) {
+        let mut useful_states = self.useful_states();
 
-        let start_state = dfa.add_state();
-        dfa.start_states.insert(start_state);
-        queue.push_back(start_state);
-        mapping.insert(start_state, nfa.start_states.clone());
-        reverse_mapping.insert(nfa.start_states.clone(), start_state);
+        if useful_states.is_disjoint(&self.start_states) {
+            useful_states.extend(self.start_states.iter());
+        }
 
-        while !queue.is_empty() {
-            // SAFETY: queue is guaranteed not to be empty
-            let curr_state = queue.pop_front().unwrap();
+        self.transitions
+            .retain(|state, _| useful_states.contains(state));
+        self.start_states
+            .retain(|state| useful_states.contains(state));
+        self.accept_states
+            .retain(|state| useful_states.contains(state));
+        self.origins
+            .retain(|state, _| useful_states.contains(state));
 
-            if used.contains(&curr_state) {
-                continue;
-            }
+        self.transitions.values_mut().for_each(|state_transitions| {
+            state_transitions.values_mut().for_each(|dest_states| {
+                dest_states.retain(|dest_state| useful_states.contains(dest_state));
+            });
 
-            // SAFETY: every queued state is mapped to some nfa states
-            let curr_mapped_to = mapping.get(&curr_state).unwrap();
-            let mut dfa_nfa_transitions =
-                BTreeMap::<AutomatonTransition, BTreeSet<AutomatonState>>::new();
+            state_transitions.retain(|_, dest_states| !dest_states.is_empty());
+        });
+    }
 
-            // Collect info about (dfa_state - char - nfa_states) transitions
-            // in order to later convert it into (dfa_state - char - dfa_state) transitions
-            curr_mapped_to.iter().for_each(|nfa_state| {
-                if nfa.accept_states.contains(nfa_state) {
-                    dfa.accept_states.insert(curr_state);
-                }
+    pub fn auto_trim(&self) -> bool {
+        self.auto_trim
+    }
 
-                // SAFETY: nfa_state is guaranteed to be in nfa
-                let nfa_transitions = nfa.transitions.get(nfa_state).unwrap();
+    pub fn set_auto_trim(&mut self, auto_trim: bool) {
+        self.auto_trim = auto_trim;
+    }
 
-                nfa_transitions.iter().for_each(|(symbol, nfa_to)| {
-                    dfa_nfa_transitions
-                        .entry(*symbol)
-                        .or_default()
-                        .extend(nfa_to.iter());
-                });
-            });
+    pub fn track_origins(&self) -> bool {
+        self.track_origins
+    }
 
-            dfa_nfa_transitions.iter().for_each(|(symbol, nfa_to)| {
-                let dfa_to = match reverse_mapping.get(&nfa_to) {
-                    Some(mapped_dfa) => *mapped_dfa,
-                    None => {
-                        let new_dfa = dfa.add_state();
-                        mapping.insert(new_dfa, nfa_to.clone());
-                        reverse_mapping.insert(nfa_to.clone(), new_dfa);
-                        queue.push_back(new_dfa);
-                        new_dfa
-                    }
-                };
+    pub fn set_track_origins(&mut self, track_origins: bool) {
+        self.track_origins = track_origins;
+    }
 
-                dfa.add_transition(curr_state, *symbol, dfa_to);
-            });
+    // Accepts exactly the mirror images of the words accepted by the automaton
+    pub fn reverse(&self) -> Self {
+        let mut reversed = Self {
+            last_state: self.last_state,
+            start_states: self.accept_states.clone(),
+            accept_states: self.start_states.clone(),
+            transitions: self
+                .transitions
+                .keys()
+                .map(|state| (*state, AutomatonTransitionList::new()))
+                .collect(),
+            alphabet: self.alphabet.clone(),
+            origins: self.origins.clone(),
+            auto_trim: self.auto_trim,
+            track_origins: self.track_origins,
+        };
 
-            used.insert(curr_state);
-        }
+        self.transitions
+            .iter()
+            .for_each(|(from, state_transitions)| {
+                state_transitions.iter().for_each(|(symbol, dest_states)| {
+                    dest_states.iter().for_each(|to| {
+                        reversed.add_transition(*to, *symbol, *from);
+                    });
+                });
+            });
 
-        dfa
+        reversed
     }
 
+    // A sink looping on itself is only added if some transition is missing,
+    // it becomes the start state of an automaton without any
     pub fn make_full(&mut self) {
         let alphabet = self.get_alphabet();
-        let drain = self.add_state();
 
-        self.transitions
-            .clone()
+        let missing: Vec<(AutomatonState, AutomatonTransition)> = self
+            .transitions
             .iter()
-            .for_each(|(state, state_transitions)| {
+            .flat_map(|(state, state_transitions)| {
                 alphabet
                     .iter()
-                    .filter(|symbol| state_transitions.get(symbol).is_none())
-                    .for_each(|symbol| {
-                        self.add_transition(*state, *symbol, drain);
-                    });
-            });
+                    .filter(|symbol| state_transitions.get(symbol).map_or(0, BTreeSet::len) == 0)
+                    .map(|symbol| (*state, *symbol))
+            })
+            .collect();
+
+        if missing.is_empty() && !self.start_states.is_empty() {
+            return;
+        }
+
+        let sink = self.add_state();
+
+        if self.start_states.is_empty() {
+            self.start_states.insert(sink);
+        }
+
+        missing.iter().for_each(|(state, symbol)| {
+            self.add_transition(*state, *symbol, sink);
+        });
+
+        alphabet.iter().for_each(|symbol| {
+            self.add_transition(sink, *symbol, sink);
+        });
     }
 
+    // Nondeterministic automatons get determinized and every automaton
+    // gets completed over its alphabet first
     pub fn make_complement(&mut self) {
+        if !self.is_deterministic() {
+            *self = self.determinized();
+        }
+
+        self.make_full();
+
         self.accept_states = self
             .transitions
             .keys()
@@ -304,168 +492,173 @@ impl FiniteAutomaton {
             .collect();
     }
 
-    pub fn make_minimal(&mut self) {
-        let mut queue = VecDeque::<(BTreeSet<AutomatonState>, AutomatonTransition)>::new();
-        let allphabet = self.get_alphabet();
-        let accept_class = self.accept_states.clone();
-        let non_accept_class: BTreeSet<_> = self
-            .transitions
-            .keys()
-            .copied()
-            .filter(|state| !self.accept_states.contains(state))
-            .collect();
+    // The declared symbols along with the ones appearing on the transitions
+    pub fn get_alphabet(&self) -> AutomatonAlphabet {
+        let mut alphabet = self.alphabet.clone();
 
-        allphabet.iter().for_each(|sym| {
-            queue.push_back((accept_class.clone(), *sym));
-            queue.push_back((non_accept_class.clone(), *sym));
+        self.transitions.values().for_each(|transition| {
+            transition
+                .keys()
+                .filter(|symbol| **symbol != AutomatonTransition::Epsilon)
+                .for_each(|symbol| {
+                    alphabet.insert(*symbol);
+                })
         });
 
-        let mut partition =
-            BTreeSet::<BTreeSet<AutomatonState>>::from([accept_class, non_accept_class]);
-
-        while !queue.is_empty() {
-            // SAFETY: queue is guaranteed not to be empty
-            let (splitter, symbol) = queue.pop_front().unwrap();
-
-            partition.clone().iter().for_each(|class| {
-                let (splitter_reachable, splitter_unreachable): (BTreeSet<AutomatonState>, _) =
-                    class.iter().partition(|state| {
-                        // SAFETY: every state must have been created via
-                        // new_state() and thus is present in transitions map
-                        self.transitions
-                            .get(*state)
-                            .unwrap()
-                            .get(&symbol)
-                            .unwrap_or(&BTreeSet::<AutomatonState>::new())
-                            .iter()
-                            .filter(|dest_state| splitter.contains(*dest_state))
-                            .next()
-                            .is_some()
-                    });
+        alphabet
+    }
 
-                if !splitter_reachable.is_empty() && !splitter_unreachable.is_empty() {
-                    allphabet.iter().for_each(|sym| {
-                        queue.push_back((splitter_reachable.clone(), *sym));
-                        queue.push_back((splitter_unreachable.clone(), *sym));
-                    });
+    // Declares symbols which don't have to appear on any transition,
+    // they are taken into account when completing and complementing
+    pub fn set_alphabet(&mut self, alphabet: &AutomatonAlphabet) {
+        self.alphabet = alphabet
+            .iter()
+            .filter(|symbol| **symbol != AutomatonTransition::Epsilon)
+            .copied()
+            .collect();
+    }
 
-                    partition.remove(class);
-                    partition.insert(splitter_reachable);
-                    partition.insert(splitter_unreachable);
-                }
-            });
+    fn epsilon_closure(&self, states: &BTreeSet<AutomatonState>) -> BTreeSet<AutomatonState> {
+        let mut closure = states.clone();
+        let mut stack: Vec<AutomatonState> = states.iter().copied().collect();
+
+        while let Some(state) = stack.pop() {
+            let epsilon_transitions = self
+                .transitions
+                .get(&state)
+                .and_then(|state_transitions| state_transitions.get(&AutomatonTransition::Epsilon));
+
+            epsilon_transitions
+                .into_iter()
+                .flatten()
+                .for_each(|epsilon_state| {
+                    if closure.insert(*epsilon_state) {
+                        stack.push(*epsilon_state);
+                    }
+                });
         }
 
-        let mut state_to_class_state = BTreeMap::<AutomatonState, AutomatonState>::new();
-        let mut class_to_state = BTreeMap::<BTreeSet<AutomatonState>, AutomatonState>::new();
+        closure
+    }
 
-        partition.iter().for_each(|class| {
-            let new_state = self.add_state();
-            class_to_state.insert(class.clone(), new_state);
+    // Follows the symbol from every state and takes the epsilon closure of the result
+    fn step(
+        &self,
+        states: &BTreeSet<AutomatonState>,
+        symbol: AutomatonTransition,
+    ) -> BTreeSet<AutomatonState> {
+        let mut next_states = BTreeSet::<AutomatonState>::new();
 
-            class.iter().for_each(|state| {
-                state_to_class_state.insert(*state, new_state);
-            });
-        });
+        states.iter().for_each(|state| {
+            let symbol_transitions = self
+                .transitions
+                .get(state)
+                .and_then(|state_transitions| state_transitions.get(&symbol));
 
-        self.accept_states.clone().iter().for_each(|accept_state| {
-            // SAFETY: every state must be in some equivalnce class
-            // and every equivalnce class is mapped to some new state
-            self.accept_states
-                .insert(*state_to_class_state.get(accept_state).unwrap());
+            next_states.extend(symbol_transitions.into_iter().flatten());
         });
 
-        self.start_states.clone().iter().for_each(|start_state| {
-            // SAFETY: every state must be in some equivalnce class
-            // and every equivalnce class is mapped to some new state
-            self.start_states
-                .insert(*state_to_class_state.get(start_state).unwrap());
-        });
+        self.epsilon_closure(&next_states)
+    }
 
-        partition.iter().for_each(|class| {
-            // SAFETY: all the class have been added to the map earlier
-            let class_state = class_to_state.get(class).unwrap();
-
-            class.iter().for_each(|old_state| {
-                // SAFETY: every state must have been created via
-                // new_state() and thus is present in transitions map
-                self.transitions
-                    .get(old_state)
-                    .cloned()
-                    .unwrap()
-                    .iter()
-                    .for_each(|(symbol, symbol_transitions)| {
-                        symbol_transitions.iter().for_each(|symbol_transition| {
-                            // SAFETY: every state must be in some equivalnce class
-                            // and every equivalnce class is mapped to some new state
-                            let class_transition =
-                                state_to_class_state.get(symbol_transition).unwrap();
-
-                            self.transitions
-                                .entry(*class_state)
-                                .or_default()
-                                .entry(*symbol)
-                                .or_default()
-                                .insert(*class_transition);
-                        });
-                    });
+    pub fn accepts_word(&self, word: &str) -> bool {
+        let mut curr_states = self.epsilon_closure(&self.start_states);
 
-                self.remove_state(*old_state);
-            });
-        });
+        for sym in word.chars() {
+            if curr_states.is_empty() {
+                return false;
+            }
+
+            curr_states = self.step(&curr_states, AutomatonTransition::Symbol(sym));
+        }
+
+        self.contains_accept(&curr_states)
     }
 
-    pub fn get_alphabet(&self) -> AutomatonAlphabet {
-        let mut alphabet = AutomatonAlphabet::new();
+    // The active states before reading the word followed by the ones after every symbol,
+    // epsilon closures included. A run that gets stuck continues with empty sets
+    pub fn run_trace(&self, word: &str) -> Vec<BTreeSet<AutomatonState>> {
+        let mut trace = vec![self.epsilon_closure(&self.start_states)];
 
-        self.transitions.values().for_each(|transition| {
-            transition.keys().for_each(|symbol| {
-                alphabet.insert(*symbol);
-            })
+        word.chars().for_each(|sym| {
+            // SAFETY: the trace always starts with the closure of the start states
+            let curr_states = trace.last().unwrap();
+            let next_states = self.step(curr_states, AutomatonTransition::Symbol(sym));
+            trace.push(next_states);
         });
 
-        alphabet
+        trace
     }
 
-    pub fn accepts_word(&self, word: &str) -> bool {
-        let word = word.to_string();
-        let mut curr_states = self.start_states.clone();
+    pub fn builder() -> FiniteAutomatonBuilder {
+        FiniteAutomatonBuilder::new()
+    }
 
-        for sym in word.chars() {
-            let mut next_states = BTreeSet::<AutomatonState>::new();
+    pub fn states(&self) -> impl Iterator<Item = AutomatonState> + '_ {
+        self.transitions.keys().copied()
+    }
 
-            curr_states.iter().for_each(|state| {
-                // SAFETY: every state must have been created via
-                // new_state() and thus is present in transitions map
-                let curr_trans = self.transitions.get(state).unwrap();
+    pub fn start_states(&self) -> &BTreeSet<AutomatonState> {
+        &self.start_states
+    }
 
-                if let Some(next) = curr_trans.get(&AutomatonTransition::Symbol(sym)) {
-                    next_states.extend(next.iter());
-                }
-            });
+    pub fn accept_states(&self) -> &BTreeSet<AutomatonState> {
+        &self.accept_states
+    }
 
-            if next_states.is_empty() {
-                return false;
-            }
+    pub fn is_start(&self, state: AutomatonState) -> bool {
+        self.start_states.contains(&state)
+    }
 
-            curr_states = next_states;
-        }
+    pub fn is_accept(&self, state: AutomatonState) -> bool {
+        self.accept_states.contains(&state)
+    }
 
-        curr_states
-            .iter()
-            .filter(|state| self.accept_states.contains(state))
-            .next()
-            .is_some()
+    // Every (from, symbol, to) transition sorted by source state and symbol
+    pub fn transitions(
+        &self,
+    ) -> impl Iterator<Item = (AutomatonState, AutomatonTransition, AutomatonState)> + '_ {
+        self.transitions.keys().flat_map(|from| {
+            self.transitions_from(*from)
+                .map(move |(symbol, to)| (*from, symbol, to))
+        })
+    }
+
+    pub fn transitions_from(
+        &self,
+        state: AutomatonState,
+    ) -> impl Iterator<Item = (AutomatonTransition, AutomatonState)> + '_ {
+        self.transitions
+            .get(&state)
+            .into_iter()
+            .flat_map(|state_transitions| {
+                state_transitions.iter().flat_map(|(symbol, dest_states)| {
+                    dest_states
+                        .iter()
+                        .map(move |dest_state| (*symbol, *dest_state))
+                })
+            })
+    }
+
+    // The states of the automaton this one has been built from which the given state
+    // stands for, i.e. a subset for to_dfa and a class of merged states for make_minimal.
+    // Only recorded once set_track_origins(true) has been called
+    pub fn state_origin(&self, state: AutomatonState) -> &[AutomatonState] {
+        self.origins.get(&state).map_or(&[], Vec::as_slice)
     }
 
     pub fn dump(&self, file_name: &str) -> io::Result<()> {
+        self.dump_with(file_name, &DumpOptions::default())
+    }
+
+    pub fn dump_with(&self, file_name: &str, options: &DumpOptions) -> io::Result<()> {
         // SAFETY: 'G' is known to be a valid id string
         // SAFETY: all of the required fields of the graph are initialized
         let graph = GraphBuilder::default()
             .graph_type(GraphType::DiGraph)
             .strict(false)
             .id(Identity::id("G").unwrap())
-            .stmts(self.build_graph())
+            .stmts(self.build_graph(options))
             .build()
             .unwrap();
 
@@ -484,9 +677,29 @@ impl FiniteAutomaton {
         Ok(())
     }
 
-    fn build_graph(&self) -> StmtList {
+    fn build_graph(&self, options: &DumpOptions) -> StmtList {
         let mut stmt_list = StmtList::new();
 
+        let word: Vec<char> = options.trace.iter().flat_map(|word| word.chars()).collect();
+        let trace = match &options.trace {
+            Some(word) => self.run_trace(word),
+            None => Vec::new(),
+        };
+
+        let is_active = |state: &AutomatonState| trace.iter().any(|states| states.contains(state));
+
+        // Epsilon transitions are taken within a step and symbol ones in between the steps
+        let is_taken = |from: &AutomatonState, symbol: &AutomatonTransition, to| {
+            trace.iter().enumerate().any(|(step, states)| match symbol {
+                AutomatonTransition::Epsilon => states.contains(from) && states.contains(to),
+                AutomatonTransition::Symbol(sym) => {
+                    word.get(step) == Some(sym)
+                        && states.contains(from)
+                        && trace[step + 1].contains(to)
+                }
+            })
+        };
+
         // Yes I have to loop all the states in advance in order to get the colors
         // right for them, because graphviz goes mad otherwise
         for state in self.transitions.keys() {
@@ -495,9 +708,32 @@ impl FiniteAutomaton {
                 false => color(Color::Blue),
             };
 
+            let mut node_attrs = None::<AttrList>;
+
+            // States without any known origin keep their numbers as labels
+            let origin = self.state_origin(*state);
+
+            if options.origins && !origin.is_empty() {
+                let origin: Vec<String> = origin.iter().map(ToString::to_string).collect();
+                node_attrs = Some(
+                    node_attrs
+                        .unwrap_or_default()
+                        .add_pair(label(format!("{{{}}}", origin.join(",")))),
+                );
+            }
+
+            if is_active(state) {
+                node_attrs = Some(
+                    node_attrs
+                        .unwrap_or_default()
+                        .add_pair(style(Style::Bold))
+                        .add_pair(penwidth(3_f64)),
+                );
+            }
+
             stmt_list = stmt_list
                 .add_attr(AttrType::Node, AttrList::new().add_pair(col))
-                .add_node(Identity::Usize(*state), None, None);
+                .add_node(Identity::Usize(*state), None, node_attrs);
         }
 
         // An invisible mock state to draw arrows from to the start states
@@ -524,17 +760,23 @@ impl FiniteAutomaton {
 
         for (from, transitions) in self.transitions.iter() {
             for (symbol, states) in transitions.iter() {
-                let symbol = match symbol {
+                let symbol_char = match symbol {
                     AutomatonTransition::Epsilon => '\u{03B5}',
                     AutomatonTransition::Symbol(sym) => *sym,
                 };
 
                 for to in states.iter() {
-                    stmt_list = stmt_list.add_edge(
-                        Edge::head_node(Identity::Usize(*from), None)
-                            .arrow_to_node(Identity::Usize(*to), None)
-                            .add_attrpair(label(char::to_string(&symbol))),
-                    );
+                    let mut edge = Edge::head_node(Identity::Usize(*from), None)
+                        .arrow_to_node(Identity::Usize(*to), None)
+                        .add_attrpair(label(char::to_string(&symbol_char)));
+
+                    if is_taken(from, symbol, to) {
+                        edge = edge
+                            .add_attrpair(style(Style::Bold))
+                            .add_attrpair(penwidth(3_f64));
+                    }
+
+                    stmt_list = stmt_list.add_edge(edge);
                 }
             }
         }
@@ -542,6 +784,43 @@ impl FiniteAutomaton {
         stmt_list
     }
 
+    // Copies the other automaton in between the given states
+    fn embed(&mut self, other: &Self, start_state: AutomatonState, accept_state: AutomatonState) {
+        let mapping: BTreeMap<AutomatonState, AutomatonState> = other
+            .transitions
+            .keys()
+            .map(|state| (*state, self.add_state()))
+            .collect();
+
+        other
+            .transitions
+            .iter()
+            .for_each(|(from, state_transitions)| {
+                state_transitions.iter().for_each(|(symbol, dest_states)| {
+                    dest_states.iter().for_each(|to| {
+                        // SAFETY: every state of the other automaton has been mapped
+                        self.add_transition(mapping[from], *symbol, mapping[to]);
+                    });
+                });
+            });
+
+        other.start_states.iter().for_each(|other_start| {
+            self.add_transition(
+                start_state,
+                AutomatonTransition::Epsilon,
+                mapping[other_start],
+            );
+        });
+
+        other.accept_states.iter().for_each(|other_accept| {
+            self.add_transition(
+                mapping[other_accept],
+                AutomatonTransition::Epsilon,
+                accept_state,
+            );
+        });
+    }
+
     pub(super) fn add_transition(
         &mut self,
         from: AutomatonState,
@@ -569,6 +848,7 @@ impl FiniteAutomaton {
     ) -> Option<AutomatonTransitionList> {
         self.start_states.remove(&state);
         self.accept_states.remove(&state);
+        self.origins.remove(&state);
         self.transitions.remove(&state)
     }
 }
@@ -576,6 +856,12 @@ impl FiniteAutomaton {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::LanguageEquivalence;
+
+    // Shared with the tests of the other modules
+    pub(super) fn from_string(expr: &str) -> FiniteAutomaton {
+        FiniteAutomaton::from_regex(&Regex::from_string(expr))
+    }
 
     #[test]
     fn nfa_to_dfa_unit_1() {
@@ -606,6 +892,10 @@ mod tests {
                     ]),
                 ),
             ]),
+            alphabet: AutomatonAlphabet::new(),
+            origins: BTreeMap::new(),
+            auto_trim: false,
+            track_origins: false,
         };
 
         let dfa = FiniteAutomaton::to_dfa(&nfa);
@@ -663,6 +953,10 @@ mod tests {
                     BTreeMap::from([(AutomatonTransition::Symbol('a'), BTreeSet::from([]))]),
                 ),
             ]),
+            alphabet: AutomatonAlphabet::new(),
+            origins: BTreeMap::new(),
+            auto_trim: false,
+            track_origins: false,
         };
 
         let dfa = FiniteAutomaton::to_dfa(&nfa);
@@ -741,6 +1035,10 @@ mod tests {
                     ]),
                 ),
             ]),
+            alphabet: AutomatonAlphabet::new(),
+            origins: BTreeMap::new(),
+            auto_trim: false,
+            track_origins: false,
         };
 
         let dfa = FiniteAutomaton::to_dfa(&nfa);
@@ -818,6 +1116,389 @@ mod tests {
     }
 
     #[test]
+    fn from_regex_unit_1() {
+        let regex = Regex::from_string("[a-c]x[^ab].");
+        let alphabet =
+            AutomatonAlphabet::from_iter("abcxy".chars().map(AutomatonTransition::Symbol));
+
+        let mut nfa = FiniteAutomaton::from_regex_with_alphabet(&regex, &alphabet);
+        nfa.eliminate_epsilon();
+
+        assert!(nfa.accepts_word("axcy"));
+        assert!(nfa.accepts_word("bxya"));
+        assert!(nfa.accepts_word("cxxx"));
+        assert!(!nfa.accepts_word("axay"));
+        assert!(!nfa.accepts_word("yxcy"));
+        assert!(!nfa.accepts_word("axcz"));
+
+        // There's nothing to resolve them against otherwise
+        assert_eq!(
+            FiniteAutomaton::try_from_regex(&regex).unwrap_err(),
+            FromRegexError::UndeclaredAlphabet
+        );
+        assert_eq!(
+            FiniteAutomaton::try_from_regex(&Regex::from_string("~a")).unwrap_err(),
+            FromRegexError::UndeclaredAlphabet
+        );
+        assert!(FiniteAutomaton::try_from_regex(&Regex::from_string("[a-c]x")).is_ok());
+    }
+
+    #[test]
+    fn from_regex_unit_2() {
+        let mut nfa = FiniteAutomaton::from_regex(&Regex::from_string("[0-9]{4}-(ab){1,2}c{2,}"));
+        nfa.eliminate_epsilon();
+
+        assert!(nfa.accepts_word("1234-abcc"));
+        assert!(nfa.accepts_word("0000-ababccc"));
+        assert!(!nfa.accepts_word("123-abcc"));
+        assert!(!nfa.accepts_word("12345-abcc"));
+        assert!(!nfa.accepts_word("1234-abababcc"));
+        assert!(!nfa.accepts_word("1234-abc"));
+
+        let mut nfa = FiniteAutomaton::from_regex(&Regex::from_string("a{0}b"));
+        nfa.eliminate_epsilon();
+
+        assert!(nfa.accepts_word("b"));
+        assert!(!nfa.accepts_word("ab"));
+    }
+
+    #[test]
+    fn from_regex_unit_3() {
+        let regex = Regex::from_string("(a|b)*a(a|b)* & (a|b)*b(a|b)*");
+        let mut nfa = FiniteAutomaton::from_regex(&regex);
+        nfa.eliminate_epsilon();
+
+        assert!(nfa.accepts_word("ab"));
+        assert!(nfa.accepts_word("bba"));
+        assert!(!nfa.accepts_word("aaa"));
+        assert!(!nfa.accepts_word("b"));
+        assert!(!nfa.accepts_word(""));
+    }
+
+    #[test]
+    fn from_regex_unit_4() {
+        let alphabet = AutomatonAlphabet::from_iter("ab".chars().map(AutomatonTransition::Symbol));
+        let mut nfa =
+            FiniteAutomaton::from_regex_with_alphabet(&Regex::from_string("~(a*)"), &alphabet);
+        nfa.eliminate_epsilon();
+
+        assert!(nfa.accepts_word("b"));
+        assert!(nfa.accepts_word("aab"));
+        assert!(!nfa.accepts_word(""));
+        assert!(!nfa.accepts_word("aa"));
+
+        // Matches A but not B
+        let alphabet = AutomatonAlphabet::from_iter("abc".chars().map(AutomatonTransition::Symbol));
+        let regex = Regex::from_string("[a-c]+ & ~(.*cc.*)");
+        let mut nfa = FiniteAutomaton::from_regex_with_alphabet(&regex, &alphabet);
+        nfa.eliminate_epsilon();
+
+        assert!(nfa.accepts_word("abcac"));
+        assert!(!nfa.accepts_word("abcca"));
+        assert!(!nfa.accepts_word(""));
+    }
+
+    #[test]
+    fn eliminate_epsilon_unit_1() {
+        // Nested stars make up epsilon cycles
+        let mut nfa = FiniteAutomaton::from_regex(&Regex::from_string("((a*b*)*|c)*d?"));
+        nfa.eliminate_epsilon();
+
+        assert_eq!(nfa.get_alphabet().len(), 4);
+        assert!(nfa.transitions.values().all(|state_transitions| {
+            !state_transitions.contains_key(&AutomatonTransition::Epsilon)
+        }));
+
+        assert!(nfa.accepts_word(""));
+        assert!(nfa.accepts_word("cabbad"));
+        assert!(nfa.accepts_word("d"));
+        assert!(!nfa.accepts_word("dd"));
+        assert!(!nfa.accepts_word("da"));
+    }
+
+    #[test]
+    fn nfa_to_dfa_unit_4() {
+        // Epsilon transitions don't have to be eliminated beforehand
+        let epsilon_nfa = FiniteAutomaton::from_regex(&Regex::from_string("(a|1)(ba)*b?"));
+        let dfa = FiniteAutomaton::to_dfa(&epsilon_nfa);
+
+        let mut nfa = epsilon_nfa.clone();
+        nfa.eliminate_epsilon();
+
+        assert!(dfa.is_deterministic());
+        assert!(dfa.accepts_word("abab"));
+        assert!(dfa.accepts_word("bab"));
+        assert!(!dfa.accepts_word("aa"));
+        assert_eq!(
+            FiniteAutomaton::equivalent(&dfa, &FiniteAutomaton::to_dfa(&nfa)),
+            LanguageEquivalence::Equal
+        );
+    }
+
+    #[test]
+    fn make_full_unit_1() {
+        let alphabet = AutomatonAlphabet::from_iter("ab".chars().map(AutomatonTransition::Symbol));
+        let mut nfa =
+            FiniteAutomaton::from_regex_with_alphabet(&Regex::from_string("a*"), &alphabet);
+        nfa.eliminate_epsilon();
+
+        let mut dfa = FiniteAutomaton::to_dfa(&nfa);
+        dfa.make_minimal();
+        assert_eq!(dfa.get_alphabet(), alphabet);
+
+        dfa.make_full();
+        assert_eq!(dfa.transitions.len(), 2);
+
+        // The sink loops on itself and is only added once
+        let sink = dfa.last_state - 1;
+        assert_eq!(
+            dfa.transitions[&sink],
+            BTreeMap::from([
+                (AutomatonTransition::Symbol('a'), BTreeSet::from([sink])),
+                (AutomatonTransition::Symbol('b'), BTreeSet::from([sink])),
+            ])
+        );
+
+        dfa.make_full();
+        assert_eq!(dfa.transitions.len(), 2);
+    }
+
+    #[test]
+    fn make_complement_unit_1() {
+        let alphabet = AutomatonAlphabet::from_iter("ab".chars().map(AutomatonTransition::Symbol));
+        let mut nfa =
+            FiniteAutomaton::from_regex_with_alphabet(&Regex::from_string("a*"), &alphabet);
+        nfa.eliminate_epsilon();
+        nfa.make_complement();
+
+        assert!(nfa.accepts_word("b"));
+        assert!(nfa.accepts_word("aab"));
+        assert!(!nfa.accepts_word("aa"));
+        assert!(!nfa.accepts_word(""));
+
+        // Nondeterministic automatons are determinized first
+        let mut nfa = FiniteAutomaton::from_regex(&Regex::from_string("(a|b)*a"));
+        nfa.eliminate_epsilon();
+        nfa.make_complement();
+
+        assert!(nfa.accepts_word("ab"));
+        assert!(!nfa.accepts_word("ba"));
+    }
+
+    #[test]
+    fn make_complement_unit_2() {
+        // The complement of the empty language is made of the sink alone
+        let alphabet = AutomatonAlphabet::from_iter("ab".chars().map(AutomatonTransition::Symbol));
+        let mut automaton = FiniteAutomaton::default();
+        automaton.set_alphabet(&alphabet);
+        automaton.make_complement();
+
+        assert_eq!(automaton.transitions.len(), 1);
+        assert!(automaton.accepts_word(""));
+        assert!(automaton.accepts_word("ab"));
+
+        let mut dfa = FiniteAutomaton::to_dfa(&from_string("a&b"));
+        dfa.trim();
+        dfa.make_complement();
+
+        assert!(dfa.accepts_word(""));
+        assert!(dfa.accepts_word("a"));
+        assert!(dfa.accepts_word("b"));
+        assert!(dfa.accepts_word("ba"));
+    }
+
+    #[test]
+    fn trim_unit_1() {
+        let mut automaton = FiniteAutomaton::default();
+        let states: Vec<AutomatonState> = (0..6).map(|_| automaton.add_state()).collect();
+
+        automaton.start_states.insert(states[0]);
+        automaton.accept_states.insert(states[1]);
+        automaton.accept_states.insert(states[4]);
+
+        let symbol = AutomatonTransition::Symbol('a');
+        automaton.add_transition(states[0], symbol, states[1]);
+        automaton.add_transition(states[0], symbol, states[2]);
+
+        // A cycle which can't reach acceptance
+        automaton.add_transition(states[2], symbol, states[3]);
+        automaton.add_transition(states[3], symbol, states[2]);
+
+        // A cycle which can't be reached
+        automaton.add_transition(states[4], symbol, states[5]);
+        automaton.add_transition(states[5], symbol, states[4]);
+
+        let mut eliminated = automaton.clone();
+        eliminated.eliminate_dead();
+        assert_eq!(eliminated.transitions.len(), 6);
+
+        automaton.trim();
+        assert_eq!(automaton.accept_states, BTreeSet::from([states[1]]));
+        assert_eq!(
+            automaton.transitions,
+            BTreeMap::from([
+                (
+                    states[0],
+                    BTreeMap::from([(symbol, BTreeSet::from([states[1]]))])
+                ),
+                (states[1], BTreeMap::new()),
+            ])
+        );
+    }
+
+    #[test]
+    fn trim_unit_2() {
+        let mut nfa = FiniteAutomaton::from_regex(&Regex::from_string("a(b|c)"));
+        nfa.eliminate_epsilon();
+        nfa.make_full();
+
+        let dfa = FiniteAutomaton::to_dfa(&nfa);
+        let mut minimal = dfa.clone();
+        minimal.make_minimal();
+
+        nfa.set_auto_trim(true);
+
+        let trimmed_dfa = FiniteAutomaton::to_dfa(&nfa);
+        let mut trimmed_minimal = trimmed_dfa.clone();
+        trimmed_minimal.make_minimal();
+
+        assert!(trimmed_dfa.auto_trim());
+        assert_eq!(trimmed_dfa.transitions.len() + 1, dfa.transitions.len());
+        assert_eq!(
+            trimmed_minimal.transitions.len() + 1,
+            minimal.transitions.len()
+        );
+        assert!(trimmed_minimal.accepts_word("ab"));
+        assert!(!trimmed_minimal.accepts_word("a"));
+    }
+
+    #[test]
+    fn trim_unit_3() {
+        // Nothing but the start state is left of an empty language
+        let mut automaton = from_string("a&b");
+        automaton.trim();
+
+        assert_eq!(automaton.start_states.len(), 1);
+        assert_eq!(automaton.transitions.len(), 1);
+        assert!(automaton.accept_states.is_empty());
+        assert!(automaton.is_empty());
+
+        let mut dfa = FiniteAutomaton::to_dfa(&from_string("a&b"));
+        dfa.make_minimal();
+        dfa.trim();
+
+        assert_eq!(dfa.start_states.len(), 1);
+        assert_eq!(dfa.transitions.len(), 1);
+    }
+
+    #[test]
+    fn state_origin_unit_1() {
+        let mut nfa = FiniteAutomaton::default();
+        let states: Vec<AutomatonState> = (0..4).map(|_| nfa.add_state()).collect();
+
+        nfa.start_states.insert(states[0]);
+        nfa.accept_states.extend([states[2], states[3]]);
+        nfa.add_transition(states[0], AutomatonTransition::Symbol('a'), states[1]);
+        nfa.add_transition(states[0], AutomatonTransition::Symbol('a'), states[2]);
+        nfa.add_transition(states[0], AutomatonTransition::Symbol('b'), states[3]);
+        nfa.add_transition(states[1], AutomatonTransition::Epsilon, states[3]);
+
+        assert!(FiniteAutomaton::to_dfa(&nfa).state_origin(1).is_empty());
+
+        nfa.set_track_origins(true);
+        let mut dfa = FiniteAutomaton::to_dfa(&nfa);
+
+        assert_eq!(dfa.state_origin(0), &[0]);
+        assert_eq!(dfa.state_origin(1), &[1, 2, 3]);
+        assert_eq!(dfa.state_origin(2), &[3]);
+        assert!(nfa.state_origin(0).is_empty());
+
+        dfa.make_minimal();
+
+        assert_eq!(dfa.state_origin(0), &[0]);
+        assert_eq!(dfa.state_origin(1), &[1, 2]);
+
+        let graph = dfa
+            .build_graph(&DumpOptions::new().with_origins(true))
+            .to_string();
+        assert!(graph.contains("label=\"{1,2}\""));
+
+        let graph = dfa.build_graph(&DumpOptions::new()).to_string();
+        assert!(!graph.contains("label=\"{1,2}\""));
+    }
+
+    #[test]
+    fn accepts_word_unit_2() {
+        // Straight out of from_regex, epsilon transitions included
+        let nfa = FiniteAutomaton::from_regex(&Regex::from_string("a((ba)*a(ab)* | a)*"));
+
+        assert!(nfa.accepts_word("a"));
+        assert!(nfa.accepts_word("abaaa"));
+        assert!(!nfa.accepts_word("abaabaab"));
+        assert!(!nfa.accepts_word(""));
+
+        assert!(FiniteAutomaton::from_regex(&Regex::from_string("(1)*")).accepts_word(""));
+        assert!(!FiniteAutomaton::default().accepts_word(""));
+    }
+
+    #[test]
+    fn run_trace_unit_1() {
+        let mut builder = FiniteAutomaton::builder();
+        let states: Vec<AutomatonState> = (0..4).map(|_| builder.add_state()).collect();
+
+        builder
+            .mark_start(states[0])
+            .mark_accept(states[3])
+            .add_epsilon(states[0], states[1])
+            .add_transition(states[0], 'a', states[2])
+            .add_transition(states[1], 'b', states[3])
+            .add_transition(states[2], 'b', states[2]);
+
+        let automaton = builder.build().unwrap();
+
+        assert_eq!(
+            automaton.run_trace("b"),
+            vec![
+                BTreeSet::from([states[0], states[1]]),
+                BTreeSet::from([states[3]]),
+            ]
+        );
+        assert_eq!(
+            automaton.run_trace("bb"),
+            vec![
+                BTreeSet::from([states[0], states[1]]),
+                BTreeSet::from([states[3]]),
+                BTreeSet::new(),
+            ]
+        );
+
+        let graph = automaton
+            .build_graph(&DumpOptions::new().with_trace("ab"))
+            .to_string();
+
+        // States 0, 1 and 2 along with 0 -> 1, 0 -a-> 2 and 2 -b-> 2 while 1 -b-> 3 isn't taken
+        assert_eq!(graph.matches("style=bold").count(), 3 + 3);
+        assert!(graph.contains("2[style=bold;penwidth=3;]"));
+        assert!(graph.contains("1->3[label=\"b\";]"));
+    }
+
+    #[test]
+    fn reverse_unit_1() {
+        let automaton = FiniteAutomaton::from_regex(&Regex::from_string("ab*c|1"));
+        let reversed = automaton.reverse().without_epsilon();
+
+        assert!(reversed.accepts_word("cbba"));
+        assert!(reversed.accepts_word("ca"));
+        assert!(reversed.accepts_word(""));
+        assert!(!reversed.accepts_word("abbc"));
+        assert_eq!(
+            reversed.reverse().reverse().transitions,
+            reversed.transitions
+        );
+    }
+
+    #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn accepts_word_unit_1() {
         let regex = Regex::from_string("a((ba)*a(ab)* | a)*");
         let mut nfa = FiniteAutomaton::from_regex(&regex);
@@ -0,0 +1,267 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use super::{AutomatonAlphabet, AutomatonState, AutomatonTransition, FiniteAutomaton};
+
+// None stands for an implicit non-accepting sink which a side of the product
+// falls into once it runs out of transitions
+type ProductState = (Option<AutomatonState>, Option<AutomatonState>);
+
+impl FiniteAutomaton {
+    pub fn intersect(left: &Self, right: &Self) -> Self {
+        Self::product(
+            &left.without_epsilon(),
+            &right.without_epsilon(),
+            |left, right| left && right,
+        )
+    }
+
+    pub fn union(left: &Self, right: &Self) -> Self {
+        Self::product(
+            &left.without_epsilon(),
+            &right.without_epsilon(),
+            |left, right| left || right,
+        )
+    }
+
+    // The right automaton has to be determinized since a word must be
+    // rejected by all of its runs rather than by some of them
+    pub fn difference(left: &Self, right: &Self) -> Self {
+        Self::product(
+            &left.without_epsilon(),
+            &right.determinized(),
+            |left, right| left && !right,
+        )
+    }
+
+    pub fn symmetric_difference(left: &Self, right: &Self) -> Self {
+        Self::product(
+            &left.determinized(),
+            &right.determinized(),
+            |left, right| left != right,
+        )
+    }
+
+    pub fn is_deterministic(&self) -> bool {
+        self.start_states.len() <= 1
+            && self.transitions.values().all(|state_transitions| {
+                state_transitions.iter().all(|(symbol, dest_states)| {
+                    *symbol != AutomatonTransition::Epsilon && dest_states.len() <= 1
+                })
+            })
+    }
+
+    pub(super) fn has_epsilon(&self) -> bool {
+        self.transitions.values().any(|state_transitions| {
+            state_transitions
+                .get(&AutomatonTransition::Epsilon)
+                .is_some_and(|dest_states| !dest_states.is_empty())
+        })
+    }
+
+    pub(super) fn without_epsilon(&self) -> Self {
+        let mut automaton = self.clone();
+
+        if self.has_epsilon() {
+            automaton.eliminate_epsilon();
+        }
+
+        automaton
+    }
+
+    pub(super) fn determinized(&self) -> Self {
+        match self.is_deterministic() {
+            true => self.clone(),
+            false => Self::to_dfa(self),
+        }
+    }
+
+    // Both automatons are expected not to have any epsilon transitions.
+    // A product state is accepting iff accept(left_accepts, right_accepts) holds
+    fn product(left: &Self, right: &Self, accept: fn(bool, bool) -> bool) -> Self {
+        let mut product = Self::default();
+        let mut mapping = BTreeMap::<ProductState, AutomatonState>::new();
+        let mut queue = VecDeque::<ProductState>::new();
+
+        let alphabet: AutomatonAlphabet = left
+            .get_alphabet()
+            .union(&right.get_alphabet())
+            .filter(|symbol| **symbol != AutomatonTransition::Epsilon)
+            .copied()
+            .collect();
+
+        product.set_alphabet(&alphabet);
+
+        let is_live = |(left_state, right_state): &ProductState| -> bool {
+            let left_flags: &[bool] = match left_state {
+                Some(_) => &[false, true],
+                None => &[false],
+            };
+
+            let right_flags: &[bool] = match right_state {
+                Some(_) => &[false, true],
+                None => &[false],
+            };
+
+            left_flags.iter().any(|left_flag| {
+                right_flags
+                    .iter()
+                    .any(|right_flag| accept(*left_flag, *right_flag))
+            })
+        };
+
+        Self::product_starts(left).iter().for_each(|left_start| {
+            Self::product_starts(right).iter().for_each(|right_start| {
+                let start = (*left_start, *right_start);
+
+                if is_live(&start) && !mapping.contains_key(&start) {
+                    let start_state = product.add_state();
+                    product.start_states.insert(start_state);
+                    mapping.insert(start, start_state);
+                    queue.push_back(start);
+                }
+            });
+        });
+
+        while let Some(curr) = queue.pop_front() {
+            // SAFETY: every queued pair has been mapped to some product state
+            let curr_state = *mapping.get(&curr).unwrap();
+            let (left_state, right_state) = curr;
+
+            let left_accepts = left_state.is_some_and(|state| left.accept_states.contains(&state));
+            let right_accepts =
+                right_state.is_some_and(|state| right.accept_states.contains(&state));
+
+            if accept(left_accepts, right_accepts) {
+                product.accept_states.insert(curr_state);
+            }
+
+            alphabet.iter().for_each(|symbol| {
+                let left_next = Self::product_successors(left, left_state, symbol);
+                let right_next = Self::product_successors(right, right_state, symbol);
+
+                left_next.iter().for_each(|left_to| {
+                    right_next.iter().for_each(|right_to| {
+                        let next = (*left_to, *right_to);
+
+                        if !is_live(&next) {
+                            return;
+                        }
+
+                        let next_state = match mapping.get(&next) {
+                            Some(mapped) => *mapped,
+                            None => {
+                                let new_state = product.add_state();
+                                mapping.insert(next, new_state);
+                                queue.push_back(next);
+                                new_state
+                            }
+                        };
+
+                        product.add_transition(curr_state, *symbol, next_state);
+                    });
+                });
+            });
+        }
+
+        product
+    }
+
+    fn product_starts(automaton: &Self) -> Vec<Option<AutomatonState>> {
+        match automaton.start_states.is_empty() {
+            true => vec![None],
+            false => automaton.start_states.iter().copied().map(Some).collect(),
+        }
+    }
+
+    fn product_successors(
+        automaton: &Self,
+        state: Option<AutomatonState>,
+        symbol: &AutomatonTransition,
+    ) -> Vec<Option<AutomatonState>> {
+        let successors = state
+            .and_then(|state| automaton.transitions.get(&state))
+            .and_then(|state_transitions| state_transitions.get(symbol));
+
+        match successors {
+            Some(successors) if !successors.is_empty() => {
+                successors.iter().copied().map(Some).collect()
+            }
+            _ => vec![None],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finite_automaton::tests::from_string;
+
+    #[test]
+    fn intersect_unit_1() {
+        let left = from_string("(a|b|c)*a(a|b|c)*");
+        let right = from_string("(a|c)*c(a|c)*");
+        let product = FiniteAutomaton::intersect(&left, &right);
+
+        assert!(product.accepts_word("ac"));
+        assert!(product.accepts_word("caa"));
+        assert!(!product.accepts_word("a"));
+        assert!(!product.accepts_word("abc"));
+        assert!(!product.accepts_word("c"));
+    }
+
+    #[test]
+    fn union_unit_1() {
+        let left = from_string("a+");
+        let right = from_string("b(a|b)");
+        let product = FiniteAutomaton::union(&left, &right);
+
+        assert!(product.accepts_word("aaa"));
+        assert!(product.accepts_word("ba"));
+        assert!(product.accepts_word("bb"));
+        assert!(!product.accepts_word(""));
+        assert!(!product.accepts_word("ab"));
+    }
+
+    #[test]
+    fn difference_unit_1() {
+        // Nondeterministic on the right: "aa" is accepted by one run and rejected by another
+        let left = from_string("a*");
+        let right = from_string("aa|a*b");
+        let product = FiniteAutomaton::difference(&left, &right);
+
+        assert!(product.accepts_word(""));
+        assert!(product.accepts_word("a"));
+        assert!(product.accepts_word("aaa"));
+        assert!(!product.accepts_word("aa"));
+        assert!(!product.accepts_word("ab"));
+    }
+
+    #[test]
+    fn symmetric_difference_unit_1() {
+        let left = from_string("a(a|b)*");
+        let right = from_string("(a|b)*b");
+        let product = FiniteAutomaton::symmetric_difference(&left, &right);
+
+        assert!(product.accepts_word("aa"));
+        assert!(product.accepts_word("bb"));
+        assert!(!product.accepts_word("ab"));
+        assert!(!product.accepts_word("ba"));
+    }
+
+    #[test]
+    fn product_unit_1() {
+        let mut left = from_string("(ab)*");
+        left.eliminate_epsilon();
+        let left = FiniteAutomaton::to_dfa(&left);
+
+        let mut right = from_string("a*b*");
+        right.eliminate_epsilon();
+        let right = FiniteAutomaton::to_dfa(&right);
+
+        assert!(FiniteAutomaton::intersect(&left, &right).is_deterministic());
+        assert!(FiniteAutomaton::union(&left, &right).is_deterministic());
+        assert!(FiniteAutomaton::difference(&left, &right).is_deterministic());
+        assert!(FiniteAutomaton::symmetric_difference(&left, &right).is_deterministic());
+        assert!(!FiniteAutomaton::union(&from_string("ab|ac"), &right).is_deterministic());
+    }
+}
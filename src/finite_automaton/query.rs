@@ -0,0 +1,321 @@
+use std::collections::BTreeMap;
+
+use super::graph::strongly_connected_components;
+use crate::{
+    AutomatonState, AutomatonTransition, FiniteAutomaton, LongestWord, SymbolCount, WordQuery,
+};
+
+// A state of the automaton along with the current value of every counter
+type QueryNode = (AutomatonState, Vec<usize>);
+
+// The part of the product of the automaton and the counters reachable from the start
+#[derive(Debug, Default, Clone)]
+struct QueryGraph {
+    nodes: Vec<QueryNode>,
+    // Transitions of every node sorted by symbol
+    transitions: Vec<Vec<(char, usize)>>,
+    parents: Vec<Option<(usize, char)>>,
+    is_goal: Vec<bool>,
+}
+
+impl WordQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, symbol: char, count: SymbolCount) -> Self {
+        self.constraints.push((symbol, count));
+        self
+    }
+
+    pub fn exactly(self, symbol: char, count: usize) -> Self {
+        self.with(symbol, SymbolCount::Exactly(count))
+    }
+
+    pub fn at_least(self, symbol: char, count: usize) -> Self {
+        self.with(symbol, SymbolCount::AtLeast(count))
+    }
+
+    pub fn at_most(self, symbol: char, count: usize) -> Self {
+        self.with(symbol, SymbolCount::AtMost(count))
+    }
+
+    pub fn modulo(self, symbol: char, modulus: usize, remainder: usize) -> Self {
+        self.with(symbol, SymbolCount::Modulo(modulus, remainder))
+    }
+
+    // Counters never leave a finite range of values, None means
+    // that the constraint can't be satisfied anymore
+    fn advance(&self, counters: &[usize], sym: char) -> Option<Vec<usize>> {
+        self.constraints
+            .iter()
+            .zip(counters.iter())
+            .map(|((symbol, count), value)| {
+                if *symbol != sym {
+                    return Some(*value);
+                }
+
+                match count {
+                    SymbolCount::Exactly(max)
+                    | SymbolCount::AtMost(max)
+                    | SymbolCount::Modulo(0, max) => Some(value + 1).filter(|next| next <= max),
+                    SymbolCount::AtLeast(min) => Some((value + 1).min(*min)),
+                    SymbolCount::Modulo(modulus, _) => Some((value + 1) % modulus),
+                }
+            })
+            .collect()
+    }
+
+    fn is_satisfied(&self, counters: &[usize]) -> bool {
+        self.constraints
+            .iter()
+            .zip(counters.iter())
+            .all(|((_, count), value)| match count {
+                SymbolCount::Exactly(expected) | SymbolCount::AtLeast(expected) => {
+                    value == expected
+                }
+                SymbolCount::AtMost(_) => true,
+                // Being congruent modulo zero means being equal
+                SymbolCount::Modulo(0, remainder) => value == remainder,
+                SymbolCount::Modulo(modulus, remainder) => *value == remainder % modulus,
+            })
+    }
+}
+
+impl FiniteAutomaton {
+    pub fn shortest_word(&self, query: &WordQuery) -> Option<String> {
+        let automaton = self.without_epsilon();
+        let graph = automaton.query_graph(query);
+
+        // Nodes are discovered in breadth-first order
+        (0..graph.nodes.len())
+            .find(|node| graph.is_goal[*node])
+            .map(|node| Self::restore_word(&graph.parents, node))
+    }
+
+    // Unbounded if arbitrarily long words satisfy the query
+    pub fn longest_word(&self, query: &WordQuery) -> Option<LongestWord> {
+        let automaton = self.without_epsilon();
+        let graph = automaton.query_graph(query);
+        let nodes_num = graph.nodes.len();
+
+        let adjacency: Vec<Vec<usize>> = graph
+            .transitions
+            .iter()
+            .map(|node_transitions| node_transitions.iter().map(|(_, to)| *to).collect())
+            .collect();
+
+        // Nodes from which some goal node can be reached
+        let mut reverse_adjacency = vec![Vec::<usize>::new(); nodes_num];
+        adjacency.iter().enumerate().for_each(|(node, successors)| {
+            successors
+                .iter()
+                .for_each(|to| reverse_adjacency[*to].push(node));
+        });
+
+        let mut is_useful = graph.is_goal.clone();
+        let mut stack: Vec<usize> = (0..nodes_num).filter(|node| is_useful[*node]).collect();
+
+        while let Some(node) = stack.pop() {
+            reverse_adjacency[node].iter().for_each(|from| {
+                if !is_useful[*from] {
+                    is_useful[*from] = true;
+                    stack.push(*from);
+                }
+            });
+        }
+
+        // Any cycle on the way to a goal node can be pumped
+        let component = strongly_connected_components(&adjacency);
+
+        let has_useful_cycle = (0..nodes_num).filter(|node| is_useful[*node]).any(|node| {
+            adjacency[node]
+                .iter()
+                .any(|to| is_useful[*to] && component[*to] == component[node])
+        });
+
+        if has_useful_cycle {
+            return Some(LongestWord::Unbounded);
+        }
+
+        let mut order: Vec<usize> = (0..nodes_num).filter(|node| is_useful[*node]).collect();
+        order.sort_by_key(|node| component[*node]);
+
+        // The longest path to some goal node, successors are processed first
+        let mut longest = vec![None::<(usize, Option<(char, usize)>)>; nodes_num];
+
+        for node in order {
+            if graph.is_goal[node] {
+                longest[node] = Some((0, None));
+            }
+
+            for (sym, to) in graph.transitions[node].iter() {
+                let Some((to_len, _)) = longest[*to] else {
+                    continue;
+                };
+
+                // None compares less than any length
+                if longest[node].map(|(len, _)| len) < Some(to_len + 1) {
+                    longest[node] = Some((to_len + 1, Some((*sym, *to))));
+                }
+            }
+        }
+
+        let start_nodes = graph.parents.iter().take_while(|parent| parent.is_none());
+
+        let (mut node, _) = start_nodes
+            .enumerate()
+            .filter_map(|(node, _)| longest[node].map(|(len, _)| (node, len)))
+            .max_by_key(|(_, len)| *len)?;
+
+        let mut word = String::new();
+
+        while let Some((_, Some((sym, to)))) = longest[node] {
+            word.push(sym);
+            node = to;
+        }
+
+        Some(LongestWord::Finite(word))
+    }
+
+    fn query_graph(&self, query: &WordQuery) -> QueryGraph {
+        let mut graph = QueryGraph::default();
+        let mut mapping = BTreeMap::<QueryNode, usize>::new();
+
+        let alphabet: Vec<AutomatonTransition> = self
+            .get_alphabet()
+            .into_iter()
+            .filter(|symbol| *symbol != AutomatonTransition::Epsilon)
+            .collect();
+
+        let mut add_node = |graph: &mut QueryGraph, node: QueryNode, parent| -> usize {
+            if let Some(idx) = mapping.get(&node) {
+                return *idx;
+            }
+
+            let idx = graph.nodes.len();
+            mapping.insert(node.clone(), idx);
+
+            graph
+                .is_goal
+                .push(self.accept_states.contains(&node.0) && query.is_satisfied(&node.1));
+            graph.nodes.push(node);
+            graph.transitions.push(Vec::new());
+            graph.parents.push(parent);
+            idx
+        };
+
+        // Start nodes go first and are the only ones without a parent
+        self.start_states.iter().for_each(|state| {
+            add_node(&mut graph, (*state, vec![0; query.constraints.len()]), None);
+        });
+
+        // Nodes are appended in discovery order, so they form the queue themselves
+        let mut curr = 0;
+
+        while curr < graph.nodes.len() {
+            let (state, counters) = graph.nodes[curr].clone();
+
+            for symbol in alphabet.iter() {
+                let AutomatonTransition::Symbol(sym) = *symbol else {
+                    continue;
+                };
+
+                let Some(next_counters) = query.advance(&counters, sym) else {
+                    continue;
+                };
+
+                let dest_states = self
+                    .transitions
+                    .get(&state)
+                    .and_then(|state_transitions| state_transitions.get(symbol));
+
+                for dest_state in dest_states.into_iter().flatten() {
+                    let next = add_node(
+                        &mut graph,
+                        (*dest_state, next_counters.clone()),
+                        Some((curr, sym)),
+                    );
+
+                    graph.transitions[curr].push((sym, next));
+                }
+            }
+
+            curr += 1;
+        }
+
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finite_automaton::tests::from_string;
+
+    #[test]
+    fn shortest_word_unit_1() {
+        let automaton = from_string("((a|b)c|a(ba)*(b|ac))*");
+
+        assert_eq!(
+            automaton.shortest_word(&WordQuery::new().exactly('a', 2)),
+            Some("aac".to_string())
+        );
+        assert_eq!(
+            automaton.shortest_word(&WordQuery::new().exactly('a', 0)),
+            Some(String::new())
+        );
+        assert_eq!(
+            automaton.shortest_word(&WordQuery::new().at_least('c', 2).at_most('a', 0)),
+            Some("bcbc".to_string())
+        );
+        assert_eq!(
+            automaton.shortest_word(&WordQuery::new().modulo('b', 3, 2).at_least('a', 1)),
+            Some("abab".to_string())
+        );
+    }
+
+    #[test]
+    fn shortest_word_unit_2() {
+        let automaton = from_string("(ab)*");
+
+        assert_eq!(
+            automaton.shortest_word(&WordQuery::new().exactly('a', 2).exactly('b', 1)),
+            None
+        );
+        assert_eq!(
+            FiniteAutomaton::default().shortest_word(&WordQuery::new()),
+            None
+        );
+        assert_eq!(
+            automaton.shortest_word(&WordQuery::new().modulo('a', 0, 3)),
+            Some("ababab".to_string())
+        );
+    }
+
+    #[test]
+    fn longest_word_unit_1() {
+        let automaton = from_string("(a|b)*");
+
+        assert_eq!(
+            automaton.longest_word(&WordQuery::new().at_most('a', 1).at_most('b', 2)),
+            Some(LongestWord::Finite("abb".to_string()))
+        );
+        assert_eq!(
+            automaton.longest_word(&WordQuery::new().at_most('a', 1)),
+            Some(LongestWord::Unbounded)
+        );
+        assert_eq!(
+            from_string("(ab)*c").longest_word(&WordQuery::new().exactly('c', 1)),
+            Some(LongestWord::Unbounded)
+        );
+        assert_eq!(
+            from_string("ab|b").longest_word(&WordQuery::new().exactly('a', 2)),
+            None
+        );
+        assert_eq!(
+            from_string("1").longest_word(&WordQuery::new()),
+            Some(LongestWord::Finite(String::new()))
+        );
+    }
+}
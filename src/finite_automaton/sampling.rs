@@ -0,0 +1,113 @@
+use num_bigint::RandBigInt;
+use rand::Rng;
+
+use super::counting::DenseDfa;
+use crate::FiniteAutomaton;
+
+impl FiniteAutomaton {
+    // Draws an accepted word of exactly the given length uniformly at random.
+    // Returns None if there are no such words
+    pub fn sample<R: Rng + ?Sized>(&self, len: usize, rng: &mut R) -> Option<String> {
+        let dfa = DenseDfa::new(self);
+        let table = dfa.count_table(len);
+        let mut curr_state = dfa.start?;
+        let mut word = String::new();
+
+        if table[len][curr_state] == Default::default() {
+            return None;
+        }
+
+        // Every symbol is chosen with the probability proportional
+        // to the number of words that can be completed after it
+        for remaining in (0..len).rev() {
+            let mut choice = rng.gen_biguint_below(&table[remaining + 1][curr_state]);
+
+            for (sym, dest_state) in dfa.transitions[curr_state].iter() {
+                let dest_words_num = &table[remaining][*dest_state];
+
+                if choice < *dest_words_num {
+                    word.push(*sym);
+                    curr_state = *dest_state;
+                    break;
+                }
+
+                choice -= dest_words_num;
+            }
+        }
+
+        Some(word)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{AutomatonAlphabet, AutomatonTransition, Regex};
+
+    #[test]
+    fn sample_unit_1() {
+        let automaton = FiniteAutomaton::from_regex(&Regex::from_string("(a|aa|b)*c"));
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for _ in 0..100 {
+            let word = automaton.sample(6, &mut rng).unwrap();
+            assert_eq!(word.chars().count(), 6);
+            assert!(word.ends_with('c') && !word[..5].contains('c'));
+        }
+
+        assert_eq!(automaton.sample(0, &mut rng), None);
+        assert_eq!(FiniteAutomaton::default().sample(3, &mut rng), None);
+    }
+
+    #[test]
+    fn sample_unit_2() {
+        // "a" is reachable through two runs, but it must be no more likely than "b"
+        let automaton = FiniteAutomaton::from_regex(&Regex::from_string("a|a|b|c"));
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut frequencies = BTreeMap::<String, usize>::new();
+
+        for _ in 0..3000 {
+            *frequencies
+                .entry(automaton.sample(1, &mut rng).unwrap())
+                .or_default() += 1;
+        }
+
+        assert_eq!(frequencies.len(), 3);
+        assert!(frequencies
+            .values()
+            .all(|frequency| (900..1100).contains(frequency)));
+    }
+
+    #[test]
+    fn sample_unit_3() {
+        let regex = Regex::from_string("[0-9]{4}-[a-z]{2}");
+
+        let first = regex.sample(7, &mut StdRng::seed_from_u64(1));
+        let second = regex.sample(7, &mut StdRng::seed_from_u64(1));
+
+        let mut nfa = FiniteAutomaton::from_regex(&regex);
+        nfa.eliminate_epsilon();
+
+        assert_eq!(first, second);
+        assert!(first.is_some_and(|word| nfa.accepts_word(&word)));
+    }
+
+    #[test]
+    fn sample_unit_4() {
+        let regex = Regex::from_string("a.");
+        let alphabet = AutomatonAlphabet::from_iter("ab".chars().map(AutomatonTransition::Symbol));
+        let mut rng = StdRng::seed_from_u64(3);
+
+        // The wildcard has nothing to be resolved against
+        assert_eq!(regex.sample(2, &mut rng), None);
+
+        for _ in 0..20 {
+            let word = regex.sample_with_alphabet(2, &alphabet, &mut rng).unwrap();
+            assert!(word == "aa" || word == "ab");
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use std::{fmt, ops::Deref};
+
+use crate::{
+    AutomatonAlphabet, AutomatonKindError, DeterminizeBudget, DeterminizeError, Dfa, EpsilonNfa,
+    FiniteAutomaton, FromRegexError, MinimizationAlgorithm, Nfa, Regex,
+};
+
+impl EpsilonNfa {
+    pub fn from_regex(regex: &Regex) -> Self {
+        Self(FiniteAutomaton::from_regex(regex))
+    }
+
+    pub fn try_from_regex(regex: &Regex) -> Result<Self, FromRegexError> {
+        FiniteAutomaton::try_from_regex(regex).map(Self)
+    }
+
+    pub fn from_regex_with_alphabet(regex: &Regex, alphabet: &AutomatonAlphabet) -> Self {
+        Self(FiniteAutomaton::from_regex_with_alphabet(regex, alphabet))
+    }
+
+    pub fn eliminate_epsilon(self) -> Nfa {
+        let mut automaton = self.0;
+        automaton.eliminate_epsilon();
+        Nfa(automaton)
+    }
+
+    pub fn to_dfa(&self) -> Dfa {
+        Dfa(FiniteAutomaton::to_dfa(&self.0))
+    }
+
+    pub fn try_to_dfa(&self, budget: DeterminizeBudget) -> Result<Dfa, DeterminizeError> {
+        FiniteAutomaton::try_to_dfa(&self.0, budget).map(Dfa)
+    }
+
+    pub fn into_automaton(self) -> FiniteAutomaton {
+        self.0
+    }
+}
+
+impl Nfa {
+    pub fn to_dfa(&self) -> Dfa {
+        Dfa(FiniteAutomaton::to_dfa(&self.0))
+    }
+
+    pub fn try_to_dfa(&self, budget: DeterminizeBudget) -> Result<Dfa, DeterminizeError> {
+        FiniteAutomaton::try_to_dfa(&self.0, budget).map(Dfa)
+    }
+
+    pub fn trim(&mut self) {
+        self.0.trim();
+    }
+
+    pub fn into_automaton(self) -> FiniteAutomaton {
+        self.0
+    }
+}
+
+impl Dfa {
+    pub fn make_full(&mut self) {
+        self.0.make_full();
+    }
+
+    pub fn make_complement(&mut self) {
+        self.0.make_complement();
+    }
+
+    pub fn make_minimal(&mut self) {
+        self.0.make_minimal();
+    }
+
+    pub fn minimize_with(&mut self, algorithm: MinimizationAlgorithm) {
+        self.0.minimize_with(algorithm);
+    }
+
+    pub fn trim(&mut self) {
+        self.0.trim();
+    }
+
+    pub fn canonicalize(&mut self) {
+        self.0.canonicalize();
+    }
+
+    pub fn into_automaton(self) -> FiniteAutomaton {
+        self.0
+    }
+}
+
+impl Deref for EpsilonNfa {
+    type Target = FiniteAutomaton;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Deref for Nfa {
+    type Target = FiniteAutomaton;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Deref for Dfa {
+    type Target = FiniteAutomaton;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+// Any automaton is an epsilon-nfa
+impl From<FiniteAutomaton> for EpsilonNfa {
+    fn from(automaton: FiniteAutomaton) -> Self {
+        Self(automaton)
+    }
+}
+
+impl From<Nfa> for EpsilonNfa {
+    fn from(nfa: Nfa) -> Self {
+        Self(nfa.0)
+    }
+}
+
+impl From<Dfa> for EpsilonNfa {
+    fn from(dfa: Dfa) -> Self {
+        Self(dfa.0)
+    }
+}
+
+impl From<Dfa> for Nfa {
+    fn from(dfa: Dfa) -> Self {
+        Self(dfa.0)
+    }
+}
+
+impl TryFrom<FiniteAutomaton> for Nfa {
+    type Error = AutomatonKindError;
+
+    fn try_from(automaton: FiniteAutomaton) -> Result<Self, Self::Error> {
+        match automaton.has_epsilon() {
+            true => Err(AutomatonKindError::HasEpsilon),
+            false => Ok(Self(automaton)),
+        }
+    }
+}
+
+impl TryFrom<FiniteAutomaton> for Dfa {
+    type Error = AutomatonKindError;
+
+    fn try_from(automaton: FiniteAutomaton) -> Result<Self, Self::Error> {
+        match automaton.is_deterministic() {
+            true => Ok(Self(automaton)),
+            false if automaton.has_epsilon() => Err(AutomatonKindError::HasEpsilon),
+            false => Err(AutomatonKindError::Nondeterministic),
+        }
+    }
+}
+
+impl fmt::Display for AutomatonKindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HasEpsilon => write!(f, "Automaton has epsilon transitions"),
+            Self::Nondeterministic => write!(f, "Automaton is nondeterministic"),
+        }
+    }
+}
+
+impl std::error::Error for AutomatonKindError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_unit_1() {
+        let regex = Regex::from_string("a((ba)*a(ab)* | a)*");
+
+        let epsilon_nfa = EpsilonNfa::from_regex(&regex);
+        assert!(epsilon_nfa.accepts_word("abaaa"));
+        assert!(!epsilon_nfa.accepts_word("abb"));
+
+        let nfa = epsilon_nfa.eliminate_epsilon();
+        assert!(nfa.accepts_word("abaaa"));
+
+        let mut dfa = nfa.to_dfa();
+        dfa.make_full();
+        dfa.make_minimal();
+        assert!(dfa.is_deterministic());
+        assert!(dfa.accepts_word("abaaa"));
+
+        dfa.make_complement();
+        assert!(!dfa.accepts_word("abaaa"));
+        assert!(dfa.accepts_word("abb"));
+    }
+
+    #[test]
+    fn typed_unit_2() {
+        let automaton = FiniteAutomaton::from_regex(&Regex::from_string("(a|b)*b"));
+
+        assert_eq!(
+            Nfa::try_from(automaton.clone()).unwrap_err(),
+            AutomatonKindError::HasEpsilon
+        );
+        assert_eq!(
+            Dfa::try_from(automaton.clone()).unwrap_err(),
+            AutomatonKindError::HasEpsilon
+        );
+
+        let nfa = EpsilonNfa::from(automaton)
+            .eliminate_epsilon()
+            .into_automaton();
+
+        assert_eq!(
+            Dfa::try_from(nfa.clone()).unwrap_err(),
+            AutomatonKindError::Nondeterministic
+        );
+
+        let dfa = Dfa::try_from(FiniteAutomaton::to_dfa(&nfa)).unwrap();
+        let nfa = Nfa::from(dfa.clone());
+        assert!(nfa.accepts_word("aab"));
+
+        let epsilon_nfa = EpsilonNfa::from(dfa);
+        assert!(!epsilon_nfa.accepts_word("aba"));
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::{BTreeSet, VecDeque};
+
+use crate::{AutomatonState, AutomatonTransition, AutomatonWords, FiniteAutomaton};
+
+impl FiniteAutomaton {
+    // Accepted words in shortlex order: shorter words go first
+    // and words of the same length are ordered lexicographically
+    pub fn words(&self) -> AutomatonWords<'_> {
+        let alphabet = self
+            .get_alphabet()
+            .iter()
+            .filter_map(|symbol| match symbol {
+                AutomatonTransition::Symbol(sym) => Some(*sym),
+                AutomatonTransition::Epsilon => None,
+            })
+            .collect();
+
+        let mut words = AutomatonWords {
+            automaton: self,
+            alphabet,
+            coreachable_states: self.coreachable_states(),
+            curr_level: VecDeque::new(),
+            next_level: VecDeque::new(),
+        };
+
+        let start_states = self.epsilon_closure(&self.start_states);
+
+        if words.is_live(&start_states) {
+            words.curr_level.push_back((String::new(), start_states));
+        }
+
+        words
+    }
+}
+
+impl AutomatonWords<'_> {
+    // Only the prefixes that can still be completed to an accepted word are kept
+    fn is_live(&self, states: &BTreeSet<AutomatonState>) -> bool {
+        states
+            .iter()
+            .any(|state| self.coreachable_states.contains(state))
+    }
+}
+
+impl Iterator for AutomatonWords<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Some((word, states)) = self.curr_level.pop_front() else {
+                if self.next_level.is_empty() {
+                    return None;
+                }
+
+                std::mem::swap(&mut self.curr_level, &mut self.next_level);
+                continue;
+            };
+
+            // Prefixes are expanded in order, so every level stays sorted
+            for sym in self.alphabet.iter() {
+                let next_states = self
+                    .automaton
+                    .step(&states, AutomatonTransition::Symbol(*sym));
+
+                if self.is_live(&next_states) {
+                    let mut next_word = word.clone();
+                    next_word.push(*sym);
+                    self.next_level.push_back((next_word, next_states));
+                }
+            }
+
+            if states
+                .iter()
+                .any(|state| self.automaton.accept_states.contains(state))
+            {
+                return Some(word);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finite_automaton::tests::from_string;
+
+    #[test]
+    fn words_unit_1() {
+        let words: Vec<String> = from_string("(a|b)*b").words().take(7).collect();
+        assert_eq!(words, ["b", "ab", "bb", "aab", "abb", "bab", "bbb"]);
+
+        // Every word has several accepting runs here
+        let words: Vec<String> = from_string("(a|aa|1)(a|aa)").words().collect();
+        assert_eq!(words, ["a", "aa", "aaa", "aaaa"]);
+    }
+
+    #[test]
+    fn words_unit_2() {
+        assert_eq!(FiniteAutomaton::default().words().next(), None);
+        assert_eq!(from_string("a & b").words().next(), None);
+
+        let words: Vec<String> = from_string("1|ba*c").words().take(3).collect();
+        assert_eq!(words, ["", "bc", "bac"]);
+    }
+}
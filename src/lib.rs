@@ -1,30 +1,88 @@
 mod finite_automaton;
 mod regular_expression;
 
-use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    io,
+    ops::Range,
+};
+
+use num_bigint::BigUint;
 
 pub type RegexEntry = Box<RegexOps>;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum RegexOps {
     Either(RegexEntry, RegexEntry),
+    Intersection(RegexEntry, RegexEntry),
     Consecutive(RegexEntry, RegexEntry),
     NoneOrMore(RegexEntry),
     NoneOrOnce(RegexEntry),
     OnceOrMore(RegexEntry),
+    // From min to max (unbounded if None) repetitions, e.g. a{2,5}
+    Repeat(RegexEntry, usize, Option<usize>),
+    // Resolved against the alphabet of the automaton, e.g. ~(ab).
+    // Binds looser than the quantifiers, so ~a* stands for ~(a*)
+    Complement(RegexEntry),
     Symbol(char),
+    // Inclusive ranges of symbols, e.g. [a-z0-9_]
+    Class(Vec<(char, char)>),
+    // Resolved against the alphabet of the automaton, e.g. [^abc]
+    NegatedClass(Vec<(char, char)>),
+    // Any symbol of the alphabet of the automaton, i.e. '.'
+    AnySymbol,
     Epsilon,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Regex {
     root: Option<RegexEntry>,
 }
 
-impl Default for Regex {
-    fn default() -> Self {
-        Self { root: None }
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegexParseErrorKind {
+    UnexpectedEnd,
+    UnexpectedSymbol(char),
+    MissingOperand(char),
+    LeftoverOperands(usize),
+    EmptyExpression,
+    EmptyClass,
+    UnknownEscape(char),
+    InvalidUnicodeEscape,
+    InvalidRange(char, char),
+    // The class spans more symbols than the given maximum
+    ClassTooLarge(usize),
+    InvalidRepetition(usize, usize),
+    RepetitionOverflow,
+    // The repetitions unroll into more symbols than the given maximum, not counting
+    // the ones wildcards, negated classes and complements get resolved to
+    RepetitionTooLarge(usize),
+    Io(io::ErrorKind),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegexToken {
+    Symbol,
+    Number,
+    Char(char),
+    End,
+}
+
+// Spans are given both in bytes and in chars of the original expression,
+// whitespace included, so that callers can slice or point at it directly
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegexParseError {
+    kind: RegexParseErrorKind,
+    expr: String,
+    byte_span: Range<usize>,
+    char_span: Range<usize>,
+    expected: Vec<RegexToken>,
+}
+
+// Renders the error with the offending part of the expression highlighted
+#[derive(Debug, Clone, Copy)]
+pub struct ColoredRegexParseError<'a> {
+    error: &'a RegexParseError,
 }
 
 pub type AutomatonState = usize;
@@ -44,6 +102,188 @@ pub struct FiniteAutomaton {
     start_states: BTreeSet<AutomatonState>,
     accept_states: BTreeSet<AutomatonState>,
     transitions: BTreeMap<AutomatonState, AutomatonTransitionList>,
+    // Symbols declared in addition to the ones appearing on the transitions
+    alphabet: AutomatonAlphabet,
+    // States of the automaton this one has been built from that every state stands for
+    origins: BTreeMap<AutomatonState, Vec<AutomatonState>>,
+    // Whether to_dfa, eliminate_epsilon and make_minimal trim their results
+    auto_trim: bool,
+    // Whether to_dfa and make_minimal record the origins of their states
+    track_origins: bool,
+}
+
+// Limits on the subset construction, nothing is limited by default
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DeterminizeBudget {
+    max_states: Option<usize>,
+    // In bytes taken by the interned subsets of nfa states
+    max_memory: Option<usize>,
+}
+
+// How far the subset construction got before running out of budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeterminizeStats {
+    dfa_states: usize,
+    processed_states: usize,
+    memory: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeterminizeError {
+    Blowup(DeterminizeStats),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FromRegexError {
+    // Negated classes, wildcards and complements only make sense against a declared alphabet
+    UndeclaredAlphabet,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MinimizationAlgorithm {
+    #[default]
+    Hopcroft,
+    Moore,
+    // Determinizes the reversed automaton twice, works on nfas directly
+    Brzozowski,
+}
+
+// Lets automatons be put together by hand, see FiniteAutomatonBuilder::build()
+#[derive(Debug, Default, Clone)]
+pub struct FiniteAutomatonBuilder {
+    automaton: FiniteAutomaton,
+    // The first transition source used before being added
+    unknown_source: Option<AutomatonState>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutomatonBuildError {
+    // The state hasn't been added via FiniteAutomatonBuilder::add_state()
+    UnknownState(AutomatonState),
+}
+
+// Typed wrappers only expose the operations that are correct for the kind of automaton
+// they hold, FiniteAutomaton itself stays available through them
+
+// Has to be determinized before being minimized or complemented
+#[derive(Debug, Default, Clone)]
+pub struct EpsilonNfa(FiniteAutomaton);
+
+// Has no epsilon transitions, still has to be determinized before being minimized or complemented
+#[derive(Debug, Default, Clone)]
+pub struct Nfa(FiniteAutomaton);
+
+// Has at most one start state and at most one transition by every symbol from every state
+#[derive(Debug, Default, Clone)]
+pub struct Dfa(FiniteAutomaton);
+
+// Only a Dfa can be minimized or complemented, the other wrappers don't even compile
+/// ```
+/// use autore::{EpsilonNfa, Regex};
+///
+/// let mut automaton = EpsilonNfa::from_regex(&Regex::from_string("a*b")).to_dfa();
+/// automaton.make_minimal();
+/// automaton.make_complement();
+/// assert!(automaton.accepts_word("a"));
+/// ```
+///
+/// ```compile_fail
+/// use autore::{EpsilonNfa, Regex};
+///
+/// let mut automaton = EpsilonNfa::from_regex(&Regex::from_string("a*b"));
+/// automaton.make_minimal();
+/// ```
+///
+/// ```compile_fail
+/// use autore::{EpsilonNfa, Regex};
+///
+/// let mut automaton = EpsilonNfa::from_regex(&Regex::from_string("a*b"));
+/// automaton.make_complement();
+/// ```
+///
+/// ```compile_fail
+/// use autore::{EpsilonNfa, Regex};
+///
+/// let mut automaton = EpsilonNfa::from_regex(&Regex::from_string("a*b")).eliminate_epsilon();
+/// automaton.make_minimal();
+/// ```
+///
+/// ```compile_fail
+/// use autore::{EpsilonNfa, Regex};
+///
+/// let mut automaton = EpsilonNfa::from_regex(&Regex::from_string("a*b")).eliminate_epsilon();
+/// automaton.make_complement();
+/// ```
+#[cfg(doctest)]
+pub struct TypedWrapperDoctests;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutomatonKindError {
+    HasEpsilon,
+    Nondeterministic,
+}
+
+// What FiniteAutomaton::dump_with() renders besides states and transitions
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DumpOptions {
+    origins: bool,
+    // The word whose run gets highlighted
+    trace: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LanguageEquivalence {
+    Equal,
+    // The shortest word accepted by exactly one of the automatons
+    Counterexample(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LanguageInclusion {
+    Included,
+    // The shortest word accepted by the first automaton but not by the second one
+    Counterexample(String),
+}
+
+// Lazily determinizes the automaton level by level, see FiniteAutomaton::words()
+#[derive(Debug, Clone)]
+pub struct AutomatonWords<'a> {
+    automaton: &'a FiniteAutomaton,
+    alphabet: Vec<char>,
+    coreachable_states: BTreeSet<AutomatonState>,
+    curr_level: VecDeque<(String, BTreeSet<AutomatonState>)>,
+    next_level: VecDeque<(String, BTreeSet<AutomatonState>)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WordGrowth {
+    // The total number of accepted words
+    Finite(BigUint),
+    // The number of accepted words of length n is O(n^degree)
+    Polynomial(usize),
+    // The number of accepted words of length n grows as rate^n
+    Exponential(f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolCount {
+    Exactly(usize),
+    AtLeast(usize),
+    AtMost(usize),
+    // The count is congruent to the remainder modulo the modulus
+    Modulo(usize, usize),
+}
+
+// Per-symbol count constraints that an accepted word has to satisfy
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WordQuery {
+    constraints: Vec<(char, SymbolCount)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LongestWord {
+    Finite(String),
+    Unbounded,
 }
 
 pub fn min_word_len_exactly_symbol_count(
@@ -51,65 +291,8 @@ pub fn min_word_len_exactly_symbol_count(
     symbol: char,
     count: usize,
 ) -> (bool, usize) {
-    type SearchState = (AutomatonState, AutomatonTransition, usize, usize);
-
-    let mut queue = VecDeque::<SearchState>::new();
-    let mut curr_level = automaton.start_states.len();
-    let mut next_level = 0;
-    let mut depth = 0_usize;
-
-    let mut is_found = false;
-    let states_num = automaton.transitions.len();
-
-    automaton
-        .start_states
-        .iter()
-        .for_each(|state| queue.push_back((*state, AutomatonTransition::Epsilon, 0, 0)));
-
-    while !queue.is_empty() {
-        // SAFETY: queue is guaranteed not to be empty
-        let (curr_state, curr_symbol, mut curr_count, mut curr_last_met) =
-            queue.pop_front().unwrap();
-
-        curr_level -= 1;
-        curr_last_met += 1;
-
-        if let AutomatonTransition::Symbol(curr_symbol) = curr_symbol {
-            if curr_symbol == symbol {
-                curr_count += 1;
-                curr_last_met = 0;
-            }
-        };
-
-        if curr_count > count || curr_last_met > states_num {
-            continue;
-        }
-
-        if curr_count == count && automaton.accept_states.contains(&curr_state) {
-            depth += (curr_level == 0) as usize;
-            is_found = true;
-            break;
-        }
-
-        // SAFETY: curr_state is guaranteed to be in nfa
-        automaton
-            .transitions
-            .get(&curr_state)
-            .unwrap()
-            .iter()
-            .for_each(|(sym, transition)| {
-                transition.iter().for_each(|state| {
-                    queue.push_back((*state, *sym, curr_count, curr_last_met));
-                    next_level += 1;
-                })
-            });
-
-        if curr_level == 0 {
-            curr_level = next_level;
-            next_level = 0;
-            depth += 1;
-        }
+    match automaton.shortest_word(&WordQuery::new().exactly(symbol, count)) {
+        Some(word) => (true, word.chars().count()),
+        None => (false, 0),
     }
-
-    (is_found, depth - 1)
 }
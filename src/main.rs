@@ -1,11 +1,17 @@
-use std::io;
+use std::{io, process};
 
 use autore::{min_word_len_exactly_symbol_count, FiniteAutomaton, Regex};
 
 fn main() -> io::Result<()> {
     let mut rpn = String::new();
     io::stdin().read_line(&mut rpn)?;
-    let regex = Regex::from_rpn(&rpn);
+    let regex = match Regex::try_from_rpn(&rpn) {
+        Ok(regex) => regex,
+        Err(error) => {
+            eprintln!("{}", error.colored());
+            process::exit(1);
+        }
+    };
 
     let mut nfa = FiniteAutomaton::from_regex(&regex);
     nfa.eliminate_epsilon();
@@ -14,7 +20,7 @@ fn main() -> io::Result<()> {
     let read_x_k = || -> (char, usize) {
         let mut buf = String::new();
         io::stdin().read_line(&mut buf).unwrap();
-        let mut x_k = buf.trim().split_whitespace();
+        let mut x_k = buf.split_whitespace();
 
         (
             x_k.next().unwrap().parse::<char>().unwrap(),
@@ -1,45 +1,78 @@
 use std::{
     collections::{BTreeMap, BTreeSet, VecDeque},
+    fmt,
     fs::File,
     io::{self, BufReader, BufWriter, Read, Write},
-    ops::Deref,
+    ops::{Deref, Range},
 };
 
 use colored::Colorize;
+use rand::Rng;
 
-use super::{AutomatonState, AutomatonTransition, FiniteAutomaton, Regex, RegexEntry, RegexOps};
+use super::{
+    AutomatonAlphabet, AutomatonState, AutomatonTransition, ColoredRegexParseError,
+    FiniteAutomaton, LanguageEquivalence, Regex, RegexEntry, RegexOps, RegexParseError,
+    RegexParseErrorKind, RegexToken,
+};
+
+// A non-whitespace character of the expression along with its position
+#[derive(Debug, Clone, Copy)]
+struct SourceChar {
+    offset: usize,
+    index: usize,
+    symbol: char,
+}
 
 #[derive(Debug, Default, Clone)]
 struct RpnConverter {
-    stack: VecDeque<String>,
-    rpn: String,
+    expr: String,
+    chars: Vec<SourceChar>,
+    // Every operand remembers where it starts in order to point at leftovers
+    stack: Vec<(RegexEntry, usize)>,
 }
 
 #[derive(Debug, Default, Clone)]
 struct RegexParser {
     expr: String,
+    chars: Vec<SourceChar>,
     curr_pos: usize,
 }
 
 impl Regex {
     pub fn from_rpn(rpn: &str) -> Self {
-        let mut rpn_converter = RpnConverter::new(rpn.to_string());
-        let expr = rpn_converter.get_infix();
-        let mut regex_parser = RegexParser::new(expr.to_string());
-        regex_parser.get_regex()
+        Self::try_from_rpn(rpn).unwrap_or_else(|error| panic!("{}", error.colored()))
     }
 
     pub fn from_string(expr: &str) -> Self {
+        Self::try_from_string(expr).unwrap_or_else(|error| panic!("{}", error.colored()))
+    }
+
+    pub fn from_file(file: &File) -> Self {
+        Self::try_from_reader(file).unwrap_or_else(|error| panic!("{}", error.colored()))
+    }
+
+    pub fn try_from_rpn(rpn: &str) -> Result<Self, RegexParseError> {
+        let mut rpn_converter = RpnConverter::new(rpn.to_string());
+        rpn_converter.get_regex()
+    }
+
+    pub fn try_from_string(expr: &str) -> Result<Self, RegexParseError> {
         let mut regex_parser = RegexParser::new(expr.to_string());
         regex_parser.get_regex()
     }
 
-    pub fn from_file(file: &File) -> Self {
-        let mut reader = BufReader::new(file);
+    pub fn try_from_reader<R: Read>(reader: R) -> Result<Self, RegexParseError> {
+        let mut reader = BufReader::new(reader);
         let mut expr = String::new();
 
         if let Err(error) = reader.read_to_string(&mut expr) {
-            panic!("Error when reading from file: {:#?}", error);
+            return Err(RegexParseError {
+                kind: RegexParseErrorKind::Io(error.kind()),
+                expr,
+                byte_span: 0..0,
+                char_span: 0..0,
+                expected: Vec::new(),
+            });
         }
 
         let mut regex_parser = RegexParser::new(expr);
@@ -194,7 +227,7 @@ impl Regex {
             .or_default()
             .entry(*to)
             .and_modify(|regex_entry| {
-                *regex_entry = Box::new(RegexOps::Either(regex.clone(), regex_entry.clone()))
+                **regex_entry = RegexOps::Either(regex.clone(), regex_entry.clone())
             })
             .or_insert(regex.clone());
     }
@@ -219,6 +252,18 @@ impl Regex {
                 Self::dump_helper(right, writer)?;
                 write!(writer, ")")?;
             }
+            RegexOps::Intersection(left, right) => {
+                write!(writer, "(")?;
+                Self::dump_helper(left, writer)?;
+                write!(writer, " & ")?;
+                Self::dump_helper(right, writer)?;
+                write!(writer, ")")?;
+            }
+            RegexOps::Complement(what) => {
+                write!(writer, "~(")?;
+                Self::dump_helper(what, writer)?;
+                write!(writer, ")")?;
+            }
             RegexOps::Consecutive(left, right) => {
                 Self::dump_helper(left, writer)?;
                 Self::dump_helper(right, writer)?;
@@ -238,164 +283,882 @@ impl Regex {
                 Self::dump_helper(what, writer)?;
                 write!(writer, ")+")?;
             }
+            RegexOps::Repeat(what, min, max) => {
+                write!(writer, "(")?;
+                Self::dump_helper(what, writer)?;
+
+                match max {
+                    Some(max) if max == min => write!(writer, "){{{}}}", min)?,
+                    Some(max) => write!(writer, "){{{},{}}}", min, max)?,
+                    None => write!(writer, "){{{},}}", min)?,
+                }
+            }
             RegexOps::Symbol(symbol) => {
-                write!(writer, "{}", symbol)?;
+                Self::dump_symbol(*symbol, writer)?;
+            }
+            RegexOps::Class(ranges) => {
+                write!(writer, "[")?;
+                Self::dump_ranges(ranges, writer)?;
+                write!(writer, "]")?;
+            }
+            RegexOps::NegatedClass(ranges) => {
+                write!(writer, "[^")?;
+                Self::dump_ranges(ranges, writer)?;
+                write!(writer, "]")?;
+            }
+            RegexOps::AnySymbol => {
+                write!(writer, ".")?;
             }
             RegexOps::Epsilon => {
-                write!(writer, "{}", '\u{03B5}')?;
+                write!(writer, "\u{03B5}")?;
             }
         };
 
         Ok(())
     }
+
+    fn dump_ranges(ranges: &[(char, char)], writer: &mut BufWriter<File>) -> io::Result<()> {
+        for (first, last) in ranges.iter() {
+            Self::dump_symbol(*first, writer)?;
+
+            if first != last {
+                write!(writer, "-")?;
+                Self::dump_symbol(*last, writer)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Escapes everything that could be mistaken for an operator or epsilon
+    fn dump_symbol(symbol: char, writer: &mut BufWriter<File>) -> io::Result<()> {
+        match symbol {
+            '\n' => write!(writer, "\\n"),
+            '\t' => write!(writer, "\\t"),
+            '\r' => write!(writer, "\\r"),
+            '1' | '\u{03B5}' => write!(writer, "\\{}", symbol),
+            _ if symbol.is_alphanumeric() => write!(writer, "{}", symbol),
+            _ if symbol.is_whitespace() || symbol.is_control() => {
+                write!(writer, "\\u{{{:X}}}", symbol as u32)
+            }
+            _ => write!(writer, "\\{}", symbol),
+        }
+    }
+
+    pub fn equivalent(&self, other: &Regex) -> LanguageEquivalence {
+        let alphabet: AutomatonAlphabet = self
+            .get_alphabet()
+            .union(&other.get_alphabet())
+            .copied()
+            .collect();
+
+        FiniteAutomaton::equivalent(
+            &FiniteAutomaton::from_regex_with_alphabet(self, &alphabet),
+            &FiniteAutomaton::from_regex_with_alphabet(other, &alphabet),
+        )
+    }
+
+    // Also returns None for regexes which need an alphabet, see sample_with_alphabet()
+    pub fn sample<R: Rng + ?Sized>(&self, len: usize, rng: &mut R) -> Option<String> {
+        FiniteAutomaton::try_from_regex(self)
+            .ok()
+            .and_then(|automaton| automaton.sample(len, rng))
+    }
+
+    pub fn sample_with_alphabet<R: Rng + ?Sized>(
+        &self,
+        len: usize,
+        alphabet: &AutomatonAlphabet,
+        rng: &mut R,
+    ) -> Option<String> {
+        FiniteAutomaton::from_regex_with_alphabet(self, alphabet).sample(len, rng)
+    }
+
+    pub fn get_alphabet(&self) -> AutomatonAlphabet {
+        let mut alphabet = AutomatonAlphabet::new();
+
+        if let Some(root) = &self.root {
+            Self::collect_alphabet(root, &mut alphabet);
+        }
+
+        alphabet
+    }
+
+    // Whether the regex has negated classes, wildcards or complements
+    pub fn needs_alphabet(&self) -> bool {
+        self.root.as_ref().is_some_and(Self::has_alphabet_ops)
+    }
+
+    fn has_alphabet_ops(curr_node: &RegexEntry) -> bool {
+        match curr_node.deref() {
+            RegexOps::Either(left, right)
+            | RegexOps::Intersection(left, right)
+            | RegexOps::Consecutive(left, right) => {
+                Self::has_alphabet_ops(left) || Self::has_alphabet_ops(right)
+            }
+            RegexOps::NoneOrMore(what)
+            | RegexOps::NoneOrOnce(what)
+            | RegexOps::OnceOrMore(what)
+            | RegexOps::Repeat(what, _, _) => Self::has_alphabet_ops(what),
+            RegexOps::Complement(_) | RegexOps::NegatedClass(_) | RegexOps::AnySymbol => true,
+            RegexOps::Symbol(_) | RegexOps::Class(_) | RegexOps::Epsilon => false,
+        }
+    }
+
+    fn collect_alphabet(curr_node: &RegexEntry, alphabet: &mut AutomatonAlphabet) {
+        match curr_node.deref() {
+            RegexOps::Either(left, right)
+            | RegexOps::Intersection(left, right)
+            | RegexOps::Consecutive(left, right) => {
+                Self::collect_alphabet(left, alphabet);
+                Self::collect_alphabet(right, alphabet);
+            }
+            RegexOps::NoneOrMore(what)
+            | RegexOps::NoneOrOnce(what)
+            | RegexOps::OnceOrMore(what)
+            | RegexOps::Repeat(what, _, _)
+            | RegexOps::Complement(what) => {
+                Self::collect_alphabet(what, alphabet);
+            }
+            RegexOps::Symbol(symbol) => {
+                alphabet.insert(AutomatonTransition::Symbol(*symbol));
+            }
+            RegexOps::Class(ranges) | RegexOps::NegatedClass(ranges) => {
+                ranges.iter().for_each(|(first, last)| {
+                    alphabet.extend((*first..=*last).map(AutomatonTransition::Symbol));
+                });
+            }
+            RegexOps::AnySymbol | RegexOps::Epsilon => {}
+        }
+    }
 }
 
 impl RpnConverter {
-    fn new(mut rpn: String) -> Self {
-        rpn.retain(|sym| !sym.is_whitespace());
+    fn new(expr: String) -> Self {
         Self {
-            stack: VecDeque::<String>::new(),
-            rpn: rpn.chars().rev().collect(),
+            chars: source_chars(&expr),
+            expr,
+            stack: Vec::new(),
         }
     }
 
-    fn get_infix(&mut self) -> String {
-        let embrace = |expr: &str| -> String {
-            let mut new_expr = '('.to_string();
-            new_expr.push_str(expr);
-            new_expr.push(')');
-            new_expr
-        };
+    fn get_regex(&mut self) -> Result<Regex, RegexParseError> {
+        for pos in 0..self.chars.len() {
+            let symbol = self.chars[pos].symbol;
 
-        // SAFETY: all operations with stack are guaranteed to return a valid entry
-        while let Some(symbol) = self.rpn.pop() {
             match symbol {
                 '.' => {
-                    let right = embrace(&self.stack.pop_back().unwrap());
-                    let mut left = embrace(&self.stack.pop_back().unwrap());
-                    left.push_str(&right);
-                    self.stack.push_back(left);
+                    let (right, _) = self.pop_operand(pos)?;
+                    let (left, start) = self.pop_operand(pos)?;
+                    let ret = Box::new(RegexOps::Consecutive(left, right));
+                    self.stack.push((ret, start));
                 }
                 '+' => {
-                    let right = embrace(&self.stack.pop_back().unwrap());
-                    let mut left = embrace(&self.stack.pop_back().unwrap());
-                    left.push('|');
-                    left.push_str(&right);
-                    self.stack.push_back(left);
+                    let (right, _) = self.pop_operand(pos)?;
+                    let (left, start) = self.pop_operand(pos)?;
+                    let ret = Box::new(RegexOps::Either(left, right));
+                    self.stack.push((ret, start));
                 }
                 '*' => {
-                    let mut expr = embrace(&self.stack.pop_back().unwrap());
-                    expr.push('*');
-                    self.stack.push_back(expr);
+                    let (what, start) = self.pop_operand(pos)?;
+                    self.stack
+                        .push((Box::new(RegexOps::NoneOrMore(what)), start));
                 }
-                _ => self.stack.push_back(symbol.to_string()),
+                '1' => self.stack.push((Box::new(RegexOps::Epsilon), pos)),
+                _ => self.stack.push((Box::new(RegexOps::Symbol(symbol)), pos)),
             }
         }
 
-        self.stack.pop_back().unwrap()
+        match self.stack.len() {
+            0 => Err(report_error(
+                &self.expr,
+                &self.chars,
+                self.chars.len(),
+                RegexParseErrorKind::EmptyExpression,
+                &[RegexToken::Symbol],
+            )),
+            1 => Ok(Regex {
+                root: self.stack.pop().map(|(root, _)| root),
+            }),
+            stack_len => Err(report_error(
+                &self.expr,
+                &self.chars,
+                self.stack[1].1,
+                RegexParseErrorKind::LeftoverOperands(stack_len - 1),
+                &[RegexToken::Char('.'), RegexToken::Char('+')],
+            )),
+        }
+    }
+
+    fn pop_operand(&mut self, pos: usize) -> Result<(RegexEntry, usize), RegexParseError> {
+        self.stack.pop().ok_or_else(|| {
+            report_error(
+                &self.expr,
+                &self.chars,
+                pos,
+                RegexParseErrorKind::MissingOperand(self.chars[pos].symbol),
+                &[RegexToken::Symbol],
+            )
+        })
     }
 }
 
 impl RegexParser {
-    fn new(mut expr: String) -> Self {
-        expr.retain(|sym| !sym.is_whitespace());
-        Self { expr, curr_pos: 0 }
+    const MAX_CLASS_SIZE: usize = 1 << 16;
+    const MAX_UNROLLED_SIZE: usize = 1 << 20;
+
+    fn new(expr: String) -> Self {
+        Self {
+            chars: source_chars(&expr),
+            expr,
+            curr_pos: 0,
+        }
     }
 
-    fn get_regex(&mut self) -> Regex {
-        Regex {
-            root: Some(self.parse_either()),
+    fn get_regex(&mut self) -> Result<Regex, RegexParseError> {
+        let root = self.parse_either()?;
+
+        if let Some(symbol) = self.peek() {
+            return Err(self.report_error(
+                RegexParseErrorKind::UnexpectedSymbol(symbol),
+                &[RegexToken::End],
+            ));
+        }
+
+        // Every repetition has been checked on its own already, but siblings add up
+        match Self::unrolled_size(&root) > Self::MAX_UNROLLED_SIZE {
+            true => Err(report_span_error(
+                &self.expr,
+                &self.chars,
+                0..self.chars.len(),
+                RegexParseErrorKind::RepetitionTooLarge(Self::MAX_UNROLLED_SIZE),
+                &[],
+            )),
+            false => Ok(Regex { root: Some(root) }),
         }
     }
 
-    fn parse_either(&mut self) -> RegexEntry {
-        let mut left = self.parse_consecutive();
+    fn parse_either(&mut self) -> Result<RegexEntry, RegexParseError> {
+        let mut left = self.parse_intersection()?;
 
-        while let Some('|') = self.expr.chars().nth(self.curr_pos) {
+        while let Some('|') = self.peek() {
             self.curr_pos += 1;
-            let right = self.parse_consecutive();
+            let right = self.parse_intersection()?;
             left = Box::new(RegexOps::Either(left, right));
         }
 
-        left
+        Ok(left)
     }
 
-    fn parse_consecutive(&mut self) -> RegexEntry {
-        let mut left = self.parse_repeat();
+    fn parse_intersection(&mut self) -> Result<RegexEntry, RegexParseError> {
+        let mut left = self.parse_consecutive()?;
+
+        while let Some('&') = self.peek() {
+            self.curr_pos += 1;
+            let right = self.parse_consecutive()?;
+            left = Box::new(RegexOps::Intersection(left, right));
+        }
+
+        Ok(left)
+    }
 
-        while let Some(symbol) = self.expr.chars().nth(self.curr_pos) {
-            // Only alhabetic characters and left paranthesis are valid options
-            if !(symbol.is_alphabetic() || symbol == '(') {
+    fn parse_consecutive(&mut self) -> Result<RegexEntry, RegexParseError> {
+        let mut left = self.parse_complement()?;
+
+        while let Some(symbol) = self.peek() {
+            // Everything but the operators and closing brackets starts a new operand
+            if Self::is_operator(symbol) {
                 break;
             }
 
-            let right = self.parse_repeat();
+            let right = self.parse_complement()?;
             left = Box::new(RegexOps::Consecutive(left, right));
         }
 
-        left
+        Ok(left)
     }
 
-    fn parse_repeat(&mut self) -> RegexEntry {
-        let mut ret = self.parse_priority();
+    fn parse_repeat(&mut self) -> Result<RegexEntry, RegexParseError> {
+        let mut ret = self.parse_priority()?;
 
-        while let Some(symbol) = self.expr.chars().nth(self.curr_pos) {
+        while let Some(symbol) = self.peek() {
             match symbol {
                 '*' => ret = Box::new(RegexOps::NoneOrMore(ret)),
                 '?' => ret = Box::new(RegexOps::NoneOrOnce(ret)),
                 '+' => ret = Box::new(RegexOps::OnceOrMore(ret)),
+                '{' => {
+                    let bounds_pos = self.curr_pos;
+                    let (min, max) = self.parse_bounds()?;
+                    ret = Box::new(RegexOps::Repeat(ret, min, max));
+
+                    if Self::unrolled_size(&ret) > Self::MAX_UNROLLED_SIZE {
+                        return Err(report_span_error(
+                            &self.expr,
+                            &self.chars,
+                            bounds_pos..self.curr_pos,
+                            RegexParseErrorKind::RepetitionTooLarge(Self::MAX_UNROLLED_SIZE),
+                            &[],
+                        ));
+                    }
+
+                    continue;
+                }
                 _ => break,
             }
 
             self.curr_pos += 1;
         }
 
-        ret
+        Ok(ret)
     }
 
-    fn parse_priority(&mut self) -> RegexEntry {
-        match self.expr.chars().nth(self.curr_pos) {
+    // Every repetition is unrolled into copies of the repeated automaton and every symbol
+    // of a class turns into a transition of its own, so nested ones multiply. Wildcards
+    // and negated classes count as one symbol and complements as their operand, since
+    // what they expand to depends on the alphabet they get resolved against
+    fn unrolled_size(curr_node: &RegexEntry) -> usize {
+        match curr_node.deref() {
+            RegexOps::Either(left, right)
+            | RegexOps::Intersection(left, right)
+            | RegexOps::Consecutive(left, right) => {
+                Self::unrolled_size(left).saturating_add(Self::unrolled_size(right))
+            }
+            RegexOps::NoneOrMore(what)
+            | RegexOps::NoneOrOnce(what)
+            | RegexOps::OnceOrMore(what)
+            | RegexOps::Complement(what) => Self::unrolled_size(what),
+            // what{m,} is unrolled into what{m} followed by what*
+            RegexOps::Repeat(what, min, max) => {
+                Self::unrolled_size(what).saturating_mul(max.unwrap_or(min.saturating_add(1)))
+            }
+            RegexOps::Class(ranges) | RegexOps::NegatedClass(ranges) => ranges
+                .iter()
+                .map(|(first, last)| (*last as usize) - (*first as usize) + 1)
+                .sum(),
+            RegexOps::Symbol(_) | RegexOps::AnySymbol | RegexOps::Epsilon => 1,
+        }
+    }
+
+    // Parses '{m}', '{m,}' and '{m,n}' bounds of a counted repetition
+    fn parse_bounds(&mut self) -> Result<(usize, Option<usize>), RegexParseError> {
+        let bounds_pos = self.curr_pos;
+        self.curr_pos += 1;
+
+        let min = self.parse_number()?;
+
+        let max = match self.peek() {
+            Some(',') => {
+                self.curr_pos += 1;
+
+                match self.peek() {
+                    Some('}') => None,
+                    _ => Some(self.parse_number()?),
+                }
+            }
+            _ => Some(min),
+        };
+
+        match self.peek() {
+            Some('}') => self.curr_pos += 1,
+            Some(symbol) => {
+                return Err(self.report_error(
+                    RegexParseErrorKind::UnexpectedSymbol(symbol),
+                    &[RegexToken::Char('}')],
+                ))
+            }
+            None => {
+                return Err(
+                    self.report_error(RegexParseErrorKind::UnexpectedEnd, &[RegexToken::Char('}')])
+                )
+            }
+        }
+
+        match max {
+            Some(max) if max < min => Err(report_error(
+                &self.expr,
+                &self.chars,
+                bounds_pos,
+                RegexParseErrorKind::InvalidRepetition(min, max),
+                &[],
+            )),
+            _ => Ok((min, max)),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<usize, RegexParseError> {
+        let number_pos = self.curr_pos;
+        let mut number = None::<usize>;
+
+        while let Some(digit) = self.peek().and_then(|symbol| symbol.to_digit(10)) {
+            number = number
+                .unwrap_or_default()
+                .checked_mul(10)
+                .and_then(|number| number.checked_add(digit as usize));
+
+            if number.is_none() {
+                return Err(report_error(
+                    &self.expr,
+                    &self.chars,
+                    number_pos,
+                    RegexParseErrorKind::RepetitionOverflow,
+                    &[],
+                ));
+            }
+
+            self.curr_pos += 1;
+        }
+
+        match (number, self.peek()) {
+            (Some(number), _) => Ok(number),
+            (None, Some(symbol)) => Err(self.report_error(
+                RegexParseErrorKind::UnexpectedSymbol(symbol),
+                &[RegexToken::Number],
+            )),
+            (None, None) => {
+                Err(self.report_error(RegexParseErrorKind::UnexpectedEnd, &[RegexToken::Number]))
+            }
+        }
+    }
+
+    fn parse_complement(&mut self) -> Result<RegexEntry, RegexParseError> {
+        match self.peek() {
+            Some('~') => {
+                self.curr_pos += 1;
+                Ok(Box::new(RegexOps::Complement(self.parse_complement()?)))
+            }
+            _ => self.parse_repeat(),
+        }
+    }
+
+    fn parse_priority(&mut self) -> Result<RegexEntry, RegexParseError> {
+        match self.peek() {
             Some('(') => {
                 self.curr_pos += 1;
-                let ret = self.parse_either();
+                let ret = self.parse_either()?;
 
-                match self.expr.chars().nth(self.curr_pos) {
+                match self.peek() {
                     Some(')') => self.curr_pos += 1,
-                    _ => self.report_error("')' expected"),
+                    Some(symbol) => {
+                        return Err(self.report_error(
+                            RegexParseErrorKind::UnexpectedSymbol(symbol),
+                            &[RegexToken::Char(')')],
+                        ))
+                    }
+                    None => {
+                        return Err(self.report_error(
+                            RegexParseErrorKind::UnexpectedEnd,
+                            &[RegexToken::Char(')')],
+                        ))
+                    }
                 }
 
-                ret
+                Ok(ret)
             }
             _ => self.parse_symbol(),
         }
     }
 
-    fn parse_symbol(&mut self) -> RegexEntry {
-        match self.expr.chars().nth(self.curr_pos) {
-            Some('1') => {
+    fn parse_symbol(&mut self) -> Result<RegexEntry, RegexParseError> {
+        let expected = [RegexToken::Symbol, RegexToken::Char('(')];
+
+        match self.peek() {
+            Some('1' | '\u{03B5}') => {
                 self.curr_pos += 1;
-                Box::new(RegexOps::Epsilon)
+                Ok(Box::new(RegexOps::Epsilon))
+            }
+            Some('\\') => Ok(Box::new(RegexOps::Symbol(self.parse_escape()?))),
+            Some('[') => {
+                self.curr_pos += 1;
+                self.parse_class()
+            }
+            Some('.') => {
+                self.curr_pos += 1;
+                Ok(Box::new(RegexOps::AnySymbol))
+            }
+            Some(symbol) if Self::is_operator(symbol) => {
+                Err(self.report_error(RegexParseErrorKind::UnexpectedSymbol(symbol), &expected))
             }
             Some(symbol) => {
                 self.curr_pos += 1;
-                Box::new(RegexOps::Symbol(symbol))
+                Ok(Box::new(RegexOps::Symbol(symbol)))
+            }
+            None => Err(self.report_error(RegexParseErrorKind::UnexpectedEnd, &expected)),
+        }
+    }
+
+    // Expects the opening bracket to have been consumed already
+    fn parse_class(&mut self) -> Result<RegexEntry, RegexParseError> {
+        let class_pos = self.curr_pos - 1;
+        let is_negated = self.peek() == Some('^');
+        self.curr_pos += is_negated as usize;
+
+        let mut ranges = Vec::<(char, char)>::new();
+
+        loop {
+            let first_pos = self.curr_pos;
+
+            let first = match self.peek() {
+                Some(']') if ranges.is_empty() => {
+                    return Err(
+                        self.report_error(RegexParseErrorKind::EmptyClass, &[RegexToken::Symbol])
+                    )
+                }
+                Some(']') => break,
+                Some(_) => self.parse_class_symbol()?,
+                None => {
+                    return Err(self.report_error(
+                        RegexParseErrorKind::UnexpectedEnd,
+                        &[RegexToken::Symbol, RegexToken::Char(']')],
+                    ))
+                }
+            };
+
+            // A dash right before the closing bracket stands for itself
+            let last = match (self.peek(), self.peek_next()) {
+                (Some('-'), Some(last)) if last != ']' => {
+                    self.curr_pos += 1;
+                    self.parse_class_symbol()?
+                }
+                _ => first,
+            };
+
+            if first > last {
+                return Err(report_error(
+                    &self.expr,
+                    &self.chars,
+                    first_pos,
+                    RegexParseErrorKind::InvalidRange(first, last),
+                    &[],
+                ));
+            }
+
+            ranges.push((first, last));
+        }
+
+        self.curr_pos += 1;
+
+        // Every symbol of the class turns into a transition of its own
+        let class_size: usize = ranges
+            .iter()
+            .map(|(first, last)| (*last as usize) - (*first as usize) + 1)
+            .sum();
+
+        if class_size > Self::MAX_CLASS_SIZE {
+            return Err(report_span_error(
+                &self.expr,
+                &self.chars,
+                class_pos..self.curr_pos,
+                RegexParseErrorKind::ClassTooLarge(Self::MAX_CLASS_SIZE),
+                &[],
+            ));
+        }
+
+        match is_negated {
+            true => Ok(Box::new(RegexOps::NegatedClass(ranges))),
+            false => Ok(Box::new(RegexOps::Class(ranges))),
+        }
+    }
+
+    fn parse_class_symbol(&mut self) -> Result<char, RegexParseError> {
+        match self.peek() {
+            Some('\\') => self.parse_escape(),
+            Some(symbol) => {
+                self.curr_pos += 1;
+                Ok(symbol)
+            }
+            None => Err(self.report_error(
+                RegexParseErrorKind::UnexpectedEnd,
+                &[RegexToken::Symbol, RegexToken::Char(']')],
+            )),
+        }
+    }
+
+    // Expects the current symbol to be the backslash
+    fn parse_escape(&mut self) -> Result<char, RegexParseError> {
+        let escape_pos = self.curr_pos;
+        self.curr_pos += 1;
+
+        let escaped = match self.peek() {
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('u') => {
+                self.curr_pos += 1;
+                return self.parse_unicode_escape(escape_pos);
+            }
+            Some(symbol) if symbol.is_ascii_digit() || !symbol.is_alphanumeric() => symbol,
+            Some('\u{03B5}') => '\u{03B5}',
+            Some(symbol) => {
+                return Err(report_error(
+                    &self.expr,
+                    &self.chars,
+                    escape_pos,
+                    RegexParseErrorKind::UnknownEscape(symbol),
+                    &[],
+                ))
+            }
+            None => {
+                return Err(
+                    self.report_error(RegexParseErrorKind::UnexpectedEnd, &[RegexToken::Symbol])
+                )
+            }
+        };
+
+        self.curr_pos += 1;
+        Ok(escaped)
+    }
+
+    // Parses the '{XXXX}' part of the '\u{XXXX}' escape sequence
+    fn parse_unicode_escape(&mut self, escape_pos: usize) -> Result<char, RegexParseError> {
+        match self.peek() {
+            Some('{') => self.curr_pos += 1,
+            Some(symbol) => {
+                return Err(self.report_error(
+                    RegexParseErrorKind::UnexpectedSymbol(symbol),
+                    &[RegexToken::Char('{')],
+                ))
             }
             None => {
-                self.report_error("unexpected end of the expression");
+                return Err(
+                    self.report_error(RegexParseErrorKind::UnexpectedEnd, &[RegexToken::Char('{')])
+                )
             }
         }
+
+        let mut code = String::new();
+
+        loop {
+            match self.peek() {
+                Some('}') => break,
+                Some(digit) if digit.is_ascii_hexdigit() && code.len() < 6 => code.push(digit),
+                Some(symbol) => {
+                    return Err(self.report_error(
+                        RegexParseErrorKind::UnexpectedSymbol(symbol),
+                        &[RegexToken::Char('}')],
+                    ))
+                }
+                None => {
+                    return Err(self.report_error(
+                        RegexParseErrorKind::UnexpectedEnd,
+                        &[RegexToken::Char('}')],
+                    ))
+                }
+            }
+
+            self.curr_pos += 1;
+        }
+
+        self.curr_pos += 1;
+
+        u32::from_str_radix(&code, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| {
+                report_error(
+                    &self.expr,
+                    &self.chars,
+                    escape_pos,
+                    RegexParseErrorKind::InvalidUnicodeEscape,
+                    &[],
+                )
+            })
     }
 
-    fn report_error(&self, error_msg: &str) -> ! {
-        let expr_str = self.expr.as_str();
-        let curr_pos = self.curr_pos;
-        panic!(
-            "Parser error ({}): {}{}{}",
-            error_msg,
-            &expr_str[..curr_pos],
-            &expr_str[curr_pos..(curr_pos + 1)].red(),
-            &expr_str[(curr_pos + 1)..]
-        );
+    fn is_operator(symbol: char) -> bool {
+        matches!(symbol, '|' | '&' | ')' | '*' | '?' | '+' | ']' | '{' | '}')
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars
+            .get(self.curr_pos)
+            .map(|source_char| source_char.symbol)
+    }
+
+    fn peek_next(&self) -> Option<char> {
+        self.chars
+            .get(self.curr_pos + 1)
+            .map(|source_char| source_char.symbol)
+    }
+
+    fn report_error(&self, kind: RegexParseErrorKind, expected: &[RegexToken]) -> RegexParseError {
+        report_error(&self.expr, &self.chars, self.curr_pos, kind, expected)
+    }
+}
+
+// Whitespace is insignificant unless it is escaped with a backslash
+fn source_chars(expr: &str) -> Vec<SourceChar> {
+    let mut chars = Vec::<SourceChar>::new();
+    let mut is_escaped = false;
+
+    for (index, (offset, symbol)) in expr.char_indices().enumerate() {
+        if is_escaped || !symbol.is_whitespace() {
+            chars.push(SourceChar {
+                offset,
+                index,
+                symbol,
+            });
+        }
+
+        is_escaped = !is_escaped && symbol == '\\';
+    }
+
+    chars
+}
+
+fn report_error(
+    expr: &str,
+    chars: &[SourceChar],
+    pos: usize,
+    kind: RegexParseErrorKind,
+    expected: &[RegexToken],
+) -> RegexParseError {
+    report_span_error(expr, chars, pos..(pos + 1), kind, expected)
+}
+
+// Points at the source chars in the given range, which is expected not to be empty
+fn report_span_error(
+    expr: &str,
+    chars: &[SourceChar],
+    span: Range<usize>,
+    kind: RegexParseErrorKind,
+    expected: &[RegexToken],
+) -> RegexParseError {
+    let (byte_span, char_span) = match (chars.get(span.start), chars.get(span.end - 1)) {
+        (Some(first), Some(last)) => (
+            first.offset..(last.offset + last.symbol.len_utf8()),
+            first.index..(last.index + 1),
+        ),
+        _ => {
+            let chars_num = expr.chars().count();
+            (expr.len()..expr.len(), chars_num..chars_num)
+        }
+    };
+
+    RegexParseError {
+        kind,
+        expr: expr.to_string(),
+        byte_span,
+        char_span,
+        expected: expected.to_vec(),
+    }
+}
+
+impl RegexParseError {
+    pub fn kind(&self) -> RegexParseErrorKind {
+        self.kind
+    }
+
+    pub fn expression(&self) -> &str {
+        &self.expr
+    }
+
+    pub fn byte_span(&self) -> Range<usize> {
+        self.byte_span.clone()
+    }
+
+    pub fn char_span(&self) -> Range<usize> {
+        self.char_span.clone()
+    }
+
+    pub fn expected(&self) -> &[RegexToken] {
+        &self.expected
+    }
+
+    pub fn colored(&self) -> ColoredRegexParseError<'_> {
+        ColoredRegexParseError { error: self }
+    }
+}
+
+impl fmt::Display for RegexParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEnd => write!(f, "unexpected end of the expression"),
+            Self::UnexpectedSymbol(symbol) => write!(f, "unexpected symbol '{}'", symbol),
+            Self::MissingOperand(operator) => write!(f, "missing operand for '{}'", operator),
+            Self::LeftoverOperands(count) => write!(f, "{} operand(s) left unused", count),
+            Self::EmptyExpression => write!(f, "empty expression"),
+            Self::EmptyClass => write!(f, "empty character class"),
+            Self::UnknownEscape(symbol) => write!(f, "unknown escape sequence '\\{}'", symbol),
+            Self::InvalidUnicodeEscape => write!(f, "invalid unicode escape sequence"),
+            Self::InvalidRepetition(min, max) => {
+                write!(f, "invalid repetition bounds {{{},{}}}", min, max)
+            }
+            Self::RepetitionOverflow => write!(f, "repetition bound is too large"),
+            Self::RepetitionTooLarge(max) => {
+                write!(f, "repetition unrolls into more than {} symbols", max)
+            }
+            Self::InvalidRange(first, last) => {
+                write!(f, "invalid character range '{}-{}'", first, last)
+            }
+            Self::ClassTooLarge(max) => {
+                write!(f, "character class has more than {} symbols", max)
+            }
+            Self::Io(kind) => write!(f, "error when reading the expression: {}", kind),
+        }
+    }
+}
+
+impl fmt::Display for RegexToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Symbol => write!(f, "symbol"),
+            Self::Number => write!(f, "number"),
+            Self::Char(symbol) => write!(f, "'{}'", symbol),
+            Self::End => write!(f, "end of the expression"),
+        }
+    }
+}
+
+impl fmt::Display for RegexParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Parser error ({}", self.kind)?;
+
+        if let RegexParseErrorKind::Io(_) = self.kind {
+            return write!(f, ")");
+        }
+
+        if !self.expected.is_empty() {
+            let expected: Vec<_> = self
+                .expected
+                .iter()
+                .map(|token| token.to_string())
+                .collect();
+            write!(f, ", expected {}", expected.join(" or "))?;
+        }
+
+        write!(f, ") at position {}", self.char_span.start)
+    }
+}
+
+impl std::error::Error for RegexParseError {}
+
+impl fmt::Display for ColoredRegexParseError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let error = self.error;
+        writeln!(f, "{}", error)?;
+
+        if let RegexParseErrorKind::Io(_) = error.kind {
+            return Ok(());
+        }
+
+        let expr = error.expr.trim_end_matches('\n');
+        let start = error.byte_span.start.min(expr.len());
+        let end = error.byte_span.end.min(expr.len());
+
+        writeln!(
+            f,
+            "{}{}{}",
+            &expr[..start],
+            expr[start..end].red(),
+            &expr[end..]
+        )?;
+
+        write!(
+            f,
+            "{}{}",
+            " ".repeat(error.char_span.start),
+            "^".red().bold()
+        )
     }
 }
 
@@ -424,6 +1187,303 @@ mod tests {
         );
     }
 
+    #[test]
+    fn from_string_unit_2() {
+        let regex = Regex::from_string("[a-z0-9_].[^ab-]");
+
+        assert_eq!(
+            regex,
+            Regex {
+                root: Some(Box::new(RegexOps::Consecutive(
+                    Box::new(RegexOps::Consecutive(
+                        Box::new(RegexOps::Class(vec![('a', 'z'), ('0', '9'), ('_', '_')])),
+                        Box::new(RegexOps::AnySymbol)
+                    )),
+                    Box::new(RegexOps::NegatedClass(vec![
+                        ('a', 'a'),
+                        ('b', 'b'),
+                        ('-', '-')
+                    ]))
+                ))),
+            }
+        );
+    }
+
+    #[test]
+    fn try_from_string_unit_3() {
+        let error = Regex::try_from_string("a[]").unwrap_err();
+        assert_eq!(error.kind(), RegexParseErrorKind::EmptyClass);
+        assert_eq!(error.char_span(), 2..3);
+
+        let error = Regex::try_from_string("[a-cz-x]").unwrap_err();
+        assert_eq!(error.kind(), RegexParseErrorKind::InvalidRange('z', 'x'));
+        assert_eq!(error.char_span(), 4..5);
+
+        let error = Regex::try_from_string("[^ab").unwrap_err();
+        assert_eq!(error.kind(), RegexParseErrorKind::UnexpectedEnd);
+
+        let error = Regex::try_from_string("a[b\\u{0}-\\u{10FFFF}]*").unwrap_err();
+        assert_eq!(
+            error.kind(),
+            RegexParseErrorKind::ClassTooLarge(RegexParser::MAX_CLASS_SIZE)
+        );
+        assert_eq!(error.char_span(), 1..20);
+    }
+
+    #[test]
+    fn from_string_unit_3() {
+        let symbols =
+            |symbols: &str| -> Vec<RegexOps> { symbols.chars().map(RegexOps::Symbol).collect() };
+
+        let mut parsed = Vec::<RegexOps>::new();
+        let mut node = Regex::from_string("\\(\\*\\|\\\\\\1 2-\\ \\u{41}\\n\\ε").root;
+
+        while let Some(entry) = node {
+            node = match *entry {
+                RegexOps::Consecutive(left, right) => {
+                    parsed.push(*right);
+                    Some(left)
+                }
+                other => {
+                    parsed.push(other);
+                    None
+                }
+            };
+        }
+
+        parsed.reverse();
+        assert_eq!(parsed, symbols("(*|\\12- A\nε"));
+
+        assert_eq!(
+            Regex::from_string("1ε").root,
+            Some(Box::new(RegexOps::Consecutive(
+                Box::new(RegexOps::Epsilon),
+                Box::new(RegexOps::Epsilon)
+            )))
+        );
+
+        assert_eq!(
+            Regex::from_string("[\\]\\--/]").root,
+            Some(Box::new(RegexOps::Class(vec![(']', ']'), ('-', '/')])))
+        );
+    }
+
+    #[test]
+    fn from_string_unit_4() {
+        assert_eq!(
+            Regex::from_string("a{2}b{1,}c{0,3}").root,
+            Some(Box::new(RegexOps::Consecutive(
+                Box::new(RegexOps::Consecutive(
+                    Box::new(RegexOps::Repeat(
+                        Box::new(RegexOps::Symbol('a')),
+                        2,
+                        Some(2)
+                    )),
+                    Box::new(RegexOps::Repeat(Box::new(RegexOps::Symbol('b')), 1, None))
+                )),
+                Box::new(RegexOps::Repeat(
+                    Box::new(RegexOps::Symbol('c')),
+                    0,
+                    Some(3)
+                ))
+            )))
+        );
+    }
+
+    #[test]
+    fn from_string_unit_5() {
+        let symbol = |symbol: char| Box::new(RegexOps::Symbol(symbol));
+
+        assert_eq!(
+            Regex::from_string("a|b&c").root,
+            Some(Box::new(RegexOps::Either(
+                symbol('a'),
+                Box::new(RegexOps::Intersection(symbol('b'), symbol('c')))
+            )))
+        );
+
+        assert_eq!(
+            Regex::from_string("~a*b").root,
+            Some(Box::new(RegexOps::Consecutive(
+                Box::new(RegexOps::Complement(Box::new(RegexOps::NoneOrMore(
+                    symbol('a')
+                )))),
+                symbol('b')
+            )))
+        );
+
+        assert_eq!(
+            Regex::from_string("(~a)*").root,
+            Some(Box::new(RegexOps::NoneOrMore(Box::new(
+                RegexOps::Complement(symbol('a'))
+            ))))
+        );
+    }
+
+    #[test]
+    fn try_from_string_unit_1() {
+        let error = Regex::try_from_string("(ab").unwrap_err();
+
+        assert_eq!(error.kind(), RegexParseErrorKind::UnexpectedEnd);
+        assert_eq!(error.byte_span(), 3..3);
+        assert_eq!(error.char_span(), 3..3);
+        assert_eq!(error.expected(), &[RegexToken::Char(')')]);
+        assert!(error.colored().to_string().ends_with('^'));
+    }
+
+    #[test]
+    fn try_from_string_unit_2() {
+        let error = Regex::try_from_string("a | *b").unwrap_err();
+
+        assert_eq!(error.kind(), RegexParseErrorKind::UnexpectedSymbol('*'));
+        assert_eq!(error.char_span(), 4..5);
+        assert_eq!(
+            error.expected(),
+            &[RegexToken::Symbol, RegexToken::Char('(')]
+        );
+
+        let error = Regex::try_from_string("\u{03B5}a)b").unwrap_err();
+
+        assert_eq!(error.kind(), RegexParseErrorKind::UnexpectedSymbol(')'));
+        assert_eq!(error.byte_span(), 3..4);
+        assert_eq!(error.char_span(), 2..3);
+        assert_eq!(error.expected(), &[RegexToken::End]);
+    }
+
+    #[test]
+    fn try_from_string_unit_4() {
+        let error = Regex::try_from_string("a\\q").unwrap_err();
+        assert_eq!(error.kind(), RegexParseErrorKind::UnknownEscape('q'));
+        assert_eq!(error.char_span(), 1..2);
+
+        let error = Regex::try_from_string("\\u{110000}").unwrap_err();
+        assert_eq!(error.kind(), RegexParseErrorKind::InvalidUnicodeEscape);
+        assert_eq!(error.char_span(), 0..1);
+
+        let error = Regex::try_from_string("a\\").unwrap_err();
+        assert_eq!(error.kind(), RegexParseErrorKind::UnexpectedEnd);
+    }
+
+    #[test]
+    fn try_from_string_unit_5() {
+        let error = Regex::try_from_string("ab{3,2}").unwrap_err();
+        assert_eq!(error.kind(), RegexParseErrorKind::InvalidRepetition(3, 2));
+        assert_eq!(error.char_span(), 2..3);
+
+        let error = Regex::try_from_string("a{,2}").unwrap_err();
+        assert_eq!(error.kind(), RegexParseErrorKind::UnexpectedSymbol(','));
+        assert_eq!(error.expected(), &[RegexToken::Number]);
+
+        let error = Regex::try_from_string("a{99999999999999999999999}").unwrap_err();
+        assert_eq!(error.kind(), RegexParseErrorKind::RepetitionOverflow);
+
+        let error = Regex::try_from_string("a{2").unwrap_err();
+        assert_eq!(error.kind(), RegexParseErrorKind::UnexpectedEnd);
+        assert_eq!(error.expected(), &[RegexToken::Char('}')]);
+
+        let error = Regex::try_from_string("a{1000000000}").unwrap_err();
+        assert_eq!(
+            error.kind(),
+            RegexParseErrorKind::RepetitionTooLarge(RegexParser::MAX_UNROLLED_SIZE)
+        );
+        assert_eq!(error.char_span(), 1..13);
+
+        let error = Regex::try_from_string("(a|b){0, 1000000}").unwrap_err();
+        assert_eq!(
+            error.kind(),
+            RegexParseErrorKind::RepetitionTooLarge(RegexParser::MAX_UNROLLED_SIZE)
+        );
+        assert_eq!(error.char_span(), 5..17);
+
+        assert!(Regex::try_from_string("a{1000}").is_ok());
+    }
+
+    #[test]
+    fn try_from_string_unit_6() {
+        // Nested repetitions multiply
+        let error = Regex::try_from_string("((a{1000}){1000}){1000}").unwrap_err();
+        assert_eq!(
+            error.kind(),
+            RegexParseErrorKind::RepetitionTooLarge(RegexParser::MAX_UNROLLED_SIZE)
+        );
+        assert_eq!(error.char_span(), 17..23);
+
+        let error = Regex::try_from_string("[\\u{0}-\\u{FFFF}]{1000}").unwrap_err();
+        assert_eq!(
+            error.kind(),
+            RegexParseErrorKind::RepetitionTooLarge(RegexParser::MAX_UNROLLED_SIZE)
+        );
+
+        assert!(Regex::try_from_string("(a{1000}){1000}").is_ok());
+
+        // So do sibling ones
+        let expr = "a{100000}".repeat(11);
+        let error = Regex::try_from_string(&expr).unwrap_err();
+        assert_eq!(
+            error.kind(),
+            RegexParseErrorKind::RepetitionTooLarge(RegexParser::MAX_UNROLLED_SIZE)
+        );
+        assert_eq!(error.char_span(), 0..expr.len());
+        assert!(Regex::try_from_string(&"a{100000}".repeat(10)).is_ok());
+        assert!(Regex::try_from_string("[\\u{0}-\\u{FFFF}]{16}").is_ok());
+    }
+
+    #[test]
+    fn dump_unit_1() {
+        let regex = Regex::from_string("(\\1|[\\--/])*\\ \\.");
+        assert!(regex.dump("img/dump_unit_1.txt").is_ok());
+
+        let file = File::open("img/dump_unit_1.txt").unwrap();
+        assert_eq!(Regex::from_file(&file), regex);
+    }
+
+    #[test]
+    fn dump_unit_2() {
+        let regex = Regex::from_string("a{2}b{1,}c{0,3}");
+        assert!(regex.dump("img/dump_unit_2.txt").is_ok());
+
+        let file = File::open("img/dump_unit_2.txt").unwrap();
+        let mut dumped = String::new();
+        BufReader::new(file).read_to_string(&mut dumped).unwrap();
+
+        assert_eq!(dumped, "(a){2}(b){1,}(c){0,3}");
+    }
+
+    #[test]
+    fn try_from_reader_unit_1() {
+        let regex = Regex::try_from_reader("(a|b)*ab\n".as_bytes());
+        assert_eq!(regex, Ok(Regex::from_string("(a|b)*ab")));
+    }
+
+    #[test]
+    fn from_rpn_unit_1() {
+        assert_eq!(Regex::from_rpn("ab+c.*"), Regex::from_string("((a|b)c)*"));
+        assert_eq!(
+            Regex::from_rpn("ab + c.aba.*.bac.+.+*"),
+            Regex::from_string("((a|b)c|a(ba)*(b|ac))*")
+        );
+        assert_eq!(Regex::from_rpn("a1+"), Regex::from_string("a|1"));
+    }
+
+    #[test]
+    fn try_from_rpn_unit_1() {
+        let error = Regex::try_from_rpn("ab.+").unwrap_err();
+        assert_eq!(error.kind(), RegexParseErrorKind::MissingOperand('+'));
+        assert_eq!(error.char_span(), 3..4);
+
+        let error = Regex::try_from_rpn("*").unwrap_err();
+        assert_eq!(error.kind(), RegexParseErrorKind::MissingOperand('*'));
+        assert_eq!(error.char_span(), 0..1);
+
+        let error = Regex::try_from_rpn("a bc.").unwrap_err();
+        assert_eq!(error.kind(), RegexParseErrorKind::LeftoverOperands(1));
+        assert_eq!(error.char_span(), 2..3);
+
+        let error = Regex::try_from_rpn(" \n").unwrap_err();
+        assert_eq!(error.kind(), RegexParseErrorKind::EmptyExpression);
+        assert_eq!(error.char_span(), 2..2);
+    }
+
     #[test]
     fn from_finite_automaton_unit_1() {
         let regex_initial = Regex::from_string("a((ba)*a(ab)* | a)*");
@@ -444,11 +1504,31 @@ mod tests {
         dfa_got.make_full();
         dfa_got.make_minimal();
 
-        assert!(dfa_initial
-            .dump("img/from_finite_automaton_unit_1_dfa_initial.dot")
-            .is_ok());
-        assert!(dfa_got
-            .dump("img/from_finite_automaton_unit_1_dfa_got.dot")
-            .is_ok());
+        assert_eq!(
+            FiniteAutomaton::equivalent(&dfa_initial, &dfa_got),
+            LanguageEquivalence::Equal
+        );
+        assert_eq!(
+            regex_initial.equivalent(&regex_got),
+            LanguageEquivalence::Equal
+        );
+    }
+
+    #[test]
+    fn equivalent_unit_1() {
+        assert_eq!(
+            Regex::from_string("(ab)*a").equivalent(&Regex::from_string("a(ba)*")),
+            LanguageEquivalence::Equal
+        );
+
+        // Negated classes are resolved against the symbols of both expressions
+        assert_eq!(
+            Regex::from_string("[^a]").equivalent(&Regex::from_string("b|ac")),
+            LanguageEquivalence::Counterexample("c".to_string())
+        );
+        assert_eq!(
+            Regex::from_string("[^a]").equivalent(&Regex::from_string("b|c")),
+            LanguageEquivalence::Equal
+        );
     }
 }